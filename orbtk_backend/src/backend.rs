@@ -2,50 +2,296 @@ use orbtk::prelude::*;
 use plotters_backend::text_anchor::{HPos, VPos};
 use plotters_backend::{
     BackendColor, BackendCoord, BackendStyle, BackendTextStyle, DrawingBackend, DrawingErrorKind,
-    FontStyle, FontTransform,
+    FontFamily, FontStyle, FontTransform,
 };
 
-#[derive(Debug)]
-pub struct OrbtkError;
-
-impl std::fmt::Display for OrbtkError {
-    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(fmt, "{:?}", self)
-    }
-}
-
-impl std::error::Error for OrbtkError {}
+use crate::clip::{ClipGuard, ClipStack};
+use crate::fonts::{self, FontMetrics, FontRegistry};
+use crate::gradient::GradientFill;
+use crate::measure::{self, TextLayout};
+use crate::pixels::{self, PixelBuffer};
+use crate::stroke::{self, LineCap, LineDash, LineJoin, LineStyle};
+use crate::OrbtkError;
 
 /// The drawing backend that is backed with a Cairo context
 pub struct OrbtkBackend<'a> {
     render_ctx: RefCell<&'a mut RenderContext2D>,
+    fonts: FontRegistry,
+    transform: Transform,
+    pixels: PixelBuffer,
+    line_dash: LineDash,
+    line_style: LineStyle,
+    clip: ClipStack,
+    gradient: GradientFill,
+    /// The clip currently set on the render context.
+    applied_clip: Option<(BackendCoord, BackendCoord)>,
+    /// Physical pixels per logical pixel.
+    scale: f64,
     width: u32,
     height: u32,
 }
 
 impl<'a> OrbtkBackend<'a> {
+    /// Creates a backend drawing `width` x `height` pixels into a render context that has no
+    /// transform set, see [`with_transform`](OrbtkBackend::with_transform) otherwise.
     pub fn new(
         render_ctx: &'a mut RenderContext2D,
         width: f64,
         height: f64,
     ) -> Result<Self, OrbtkError> {
-        let bytes = include_bytes!("Roboto-Regular.ttf");
-        render_ctx.register_font("Roboto-Regular", bytes);
+        Self::with_scale_factor(render_ctx, width, height, 1.0)
+    }
+
+    /// Creates a backend for a display with `scale` physical pixels per logical pixel.
+    ///
+    /// `width` and `height` are the logical size reported to plotters, the render context has to
+    /// be `scale` times as large. Coordinates, stroke widths, font sizes and bitmaps are scaled
+    /// when drawn, so charts are laid out as usual but stay sharp on HiDPI displays.
+    pub fn with_scale_factor(
+        render_ctx: &'a mut RenderContext2D,
+        width: f64,
+        height: f64,
+        scale: f64,
+    ) -> Result<Self, OrbtkError> {
+        Self::with_transform(render_ctx, width, height, scale, Transform::IDENTITY.0)
+    }
+
+    /// Creates a backend for a render context the embedding widget has set `transform` on, in
+    /// the `(a, b, c, d, e, f)` layout of `RenderContext2D::set_transform`.
+    ///
+    /// `RenderContext2D` has no way to read its transform back, but rotated text has to replace
+    /// it for a moment. Rotations are composed with `transform`, and the context is left with
+    /// exactly this transform afterwards. The context's transform itself isn't touched here.
+    pub fn with_transform(
+        render_ctx: &'a mut RenderContext2D,
+        width: f64,
+        height: f64,
+        scale: f64,
+        transform: [f64; 6],
+    ) -> Result<Self, OrbtkError> {
+        let values = [
+            ("backend size", width),
+            ("backend size", height),
+            ("scale factor", scale),
+        ];
+        let transform_values = transform.iter().map(|&value| ("transform", value));
+        for (context, value) in values.iter().copied().chain(transform_values) {
+            if !value.is_finite() {
+                return Err(OrbtkError::NonFiniteCoordinate { context, value });
+            }
+        }
+        if scale <= 0.0 {
+            return Err(OrbtkError::InvalidScaleFactor { scale });
+        }
+        if width < 1.0 || height < 1.0 {
+            return Err(OrbtkError::InvalidTarget { width, height });
+        }
+
+        let fonts = fonts::shared_fonts(render_ctx)?;
         let ret = Self {
             render_ctx: RefCell::new(render_ctx),
+            fonts,
+            transform: Transform(transform),
+            pixels: PixelBuffer::new(
+                (width * scale).ceil() as u32,
+                (height * scale).ceil() as u32,
+            ),
+            line_dash: LineDash::default(),
+            line_style: LineStyle::default(),
+            clip: ClipStack::default(),
+            gradient: GradientFill::default(),
+            applied_clip: None,
+            scale,
             width: width as u32,
             height: height as u32,
         };
         Ok(ret)
     }
+
+    /// Physical pixels per logical pixel.
+    pub fn scale_factor(&self) -> f64 {
+        self.scale
+    }
+
+    /// Maps a point in backend coordinates to physical pixels.
+    fn to_physical(&self, point: BackendCoord) -> (f64, f64) {
+        (point.0 as f64 * self.scale, point.1 as f64 * self.scale)
+    }
+
+    /// Registers a font under `name` and uses it whenever plotters asks for `family` in `style`.
+    ///
+    /// Families without a registered font fall back to the bundled Roboto, or to the first font
+    /// registered if the `bundled-font` feature is disabled. This only affects this backend,
+    /// [`register_font`](crate::register_font) registers a font once for all backends.
+    pub fn register_font(
+        &mut self,
+        family: FontFamily,
+        style: FontStyle,
+        name: &str,
+        bytes: &'static [u8],
+    ) -> Result<(), OrbtkError> {
+        self.fonts
+            .register(self.render_ctx.get_mut(), family, style, name, bytes)
+    }
+
+    /// Returns the ascent and descent of the font `family` in `style` resolves to, at `size`
+    /// logical pixels.
+    pub fn font_metrics(&self, family: FontFamily, style: FontStyle, size: f64) -> FontMetrics {
+        self.fonts.metrics(self.fonts.resolve(family, style), size)
+    }
+
+    /// The font registry used to resolve plotters font families.
+    pub fn fonts_mut(&mut self) -> &mut FontRegistry {
+        &mut self.fonts
+    }
+
+    /// Sets the dash pattern used by `draw_line`, `draw_path` and the outlines of `draw_rect`
+    /// and `draw_circle`. Use `LineDash::default()` to draw solid lines again.
+    pub fn set_line_dash(&mut self, dash: LineDash) {
+        self.line_dash = dash;
+    }
+
+    /// Sets the cap drawn at the ends of `draw_line` and `draw_path`, `LineCap::Butt` by default.
+    pub fn set_line_cap(&mut self, cap: LineCap) {
+        self.line_style.cap = cap;
+    }
+
+    /// Sets how the segments of `draw_path` are joined, `LineJoin::Miter` by default.
+    pub fn set_line_join(&mut self, join: LineJoin) {
+        self.line_style.join = join;
+    }
+
+    /// Sets the ratio between miter length and line width above which miter joins are beveled.
+    /// Values that aren't finite and positive are ignored, the default is 10.
+    pub fn set_miter_limit(&mut self, limit: f64) {
+        if limit.is_finite() && limit > 0.0 {
+            self.line_style.miter_limit = limit;
+        }
+    }
+
+    /// Returns a handle to the clip stack of this backend, which stays usable after the backend
+    /// was moved into a plotters drawing area.
+    ///
+    /// ```ignore
+    /// let backend = OrbtkBackend::new(&mut render_ctx, width, height)?;
+    /// let clip = backend.clip_stack();
+    /// let root = backend.into_drawing_area();
+    /// let mut chart = ChartBuilder::on(&root).build_cartesian_2d(0f32..10f32, 0f32..10f32)?;
+    /// let _plot_clip = clip.push_range(chart.plotting_area().get_pixel_range());
+    /// chart.draw_series(LineSeries::new(data, &RED))?;
+    /// ```
+    pub fn clip_stack(&self) -> ClipStack {
+        self.clip.clone()
+    }
+
+    /// Clips all following primitives to the rectangle, intersected with the current clip, until
+    /// the returned guard is dropped.
+    pub fn push_clip(&mut self, upper_left: BackendCoord, bottom_right: BackendCoord) -> ClipGuard {
+        self.clip.push(upper_left, bottom_right)
+    }
+
+    /// Returns a handle to set a gradient that replaces the solid color of filled rectangles,
+    /// circles and polygons, which stays usable after the backend was moved into a plotters
+    /// drawing area.
+    pub fn gradient_fill(&self) -> GradientFill {
+        self.gradient.clone()
+    }
+
+    /// Applies the clip stack to the render context if it changed since the last primitive.
+    fn sync_clip(&mut self) -> Result<(), OrbtkError> {
+        let clip = self.clip.effective();
+        if clip == self.applied_clip {
+            return Ok(());
+        }
+        // Buffered pixels were drawn under the old clip.
+        self.flush_pixels()?;
+
+        let render_ctx = self.render_ctx.get_mut();
+        if self.applied_clip.is_some() {
+            render_ctx.restore();
+        }
+        if let Some((upper_left, bottom_right)) = clip {
+            render_ctx.save();
+            render_ctx.begin_path();
+            let scale = self.scale;
+            render_ctx.rect(
+                upper_left.0 as f64 * scale,
+                upper_left.1 as f64 * scale,
+                (bottom_right.0 - upper_left.0) as f64 * scale,
+                (bottom_right.1 - upper_left.1) as f64 * scale,
+            );
+            render_ctx.clip();
+        }
+        self.applied_clip = clip;
+        Ok(())
+    }
+
+    /// Brings the render context up to date before a primitive is drawn.
+    fn prepare(&mut self) -> Result<(), DrawingErrorKind<OrbtkError>> {
+        self.sync_clip().map_err(DrawingErrorKind::DrawingError)?;
+        self.flush_pixels().map_err(DrawingErrorKind::DrawingError)
+    }
+
+    /// Splits a polyline in backend coordinates into the dashes that are drawn, in physical
+    /// pixels.
+    fn dashes(&self, points: &[(f64, f64)]) -> Vec<Vec<(f64, f64)>> {
+        let mut dashes = self.line_dash.apply(points);
+        for point in dashes.iter_mut().flatten() {
+            *point = (point.0 * self.scale, point.1 * self.scale);
+        }
+        dashes
+    }
+
+    /// Strokes a polyline with the dash pattern, cap and join configured on the backend.
+    fn stroke_polyline<S: BackendStyle>(
+        &self,
+        render_ctx: &mut RenderContext2D,
+        points: &[(f64, f64)],
+        style: &S,
+    ) {
+        let brush = utils::Brush::SolidColor(self.color_change(&style.color()));
+        let width = style.stroke_width() as f64 * self.scale;
+        render_ctx.begin_path();
+        if self.line_style.is_native() {
+            render_ctx.set_line_width(width);
+            render_ctx.set_stroke_style(brush);
+            for part in self.dashes(points) {
+                stroke::trace(render_ctx, &part);
+            }
+            render_ctx.stroke();
+        } else {
+            render_ctx.set_fill_style(brush);
+            for part in self.dashes(points) {
+                stroke::trace_outline(render_ctx, &part, width, self.line_style);
+            }
+            render_ctx.fill();
+        }
+    }
+
+    /// Draws the pixels collected by `draw_pixel` onto the render context.
+    ///
+    /// This happens automatically before every other primitive, on `present` and on drop.
+    pub fn flush_pixels(&mut self) -> Result<(), OrbtkError> {
+        self.pixels.flush(self.render_ctx.get_mut())
+    }
+
+    /// Converts a plotters color into an OrbTk color, keeping its alpha.
+    ///
+    /// The alpha is carried by the brush instead of `RenderContext2D::set_alpha`, which is global
+    /// state on the context and would leak into whatever the host widget draws next.
     pub fn color_change(&self, color: &BackendColor) -> Color {
-        /*Color::rgba(
-            color.rgb.0,
-            color.rgb.1,
-            color.rgb.2,
-            (color.alpha * 255.0) as u8,
-        );*/
-        Color::rgb(color.rgb.0, color.rgb.1, color.rgb.2)
+        to_rgba(color)
+    }
+
+    /// Lays out `text` as `draw_text` draws it, in physical pixels.
+    fn layout_text<'t, TStyle: BackendTextStyle>(
+        &self,
+        text: &'t str,
+        style: &TStyle,
+    ) -> Result<TextLayout<'_, 't>, DrawingErrorKind<OrbtkError>> {
+        let mut render_ctx = self.render_ctx.borrow_mut();
+        measure::layout_text(&mut render_ctx, &self.fonts, text, style, self.scale)
+            .map_err(DrawingErrorKind::DrawingError)
     }
 }
 
@@ -61,6 +307,8 @@ impl<'a> DrawingBackend for OrbtkBackend<'a> {
     }
 
     fn present(&mut self) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.flush_pixels()
+            .map_err(DrawingErrorKind::DrawingError)?;
         Ok(())
     }
 
@@ -69,12 +317,15 @@ impl<'a> DrawingBackend for OrbtkBackend<'a> {
         point: (i32, i32),
         color: BackendColor,
     ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
-        let mut render_ctx = self.render_ctx.borrow_mut();
-        render_ctx.begin_path();
-        render_ctx.set_alpha(color.alpha as f32);
-        render_ctx.set_fill_style(utils::Brush::SolidColor(self.color_change(&color)));
-
-        render_ctx.fill_rect(point.0 as f64, point.1 as f64, 1.0, 1.0);
+        self.sync_clip().map_err(DrawingErrorKind::DrawingError)?;
+        // A logical pixel covers all physical pixels whose top left corner lies inside it.
+        let (x0, y0) = self.to_physical(point);
+        let (x1, y1) = self.to_physical((point.0 + 1, point.1 + 1));
+        for y in y0.ceil() as i32..y1.ceil() as i32 {
+            for x in x0.ceil() as i32..x1.ceil() as i32 {
+                self.pixels.put((x, y), color);
+            }
+        }
         Ok(())
     }
 
@@ -84,16 +335,9 @@ impl<'a> DrawingBackend for OrbtkBackend<'a> {
         to: (i32, i32),
         style: &S,
     ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
-        let mut render_ctx = self.render_ctx.borrow_mut();
-        render_ctx.begin_path();
-        render_ctx.set_line_width(style.stroke_width() as f64);
-        render_ctx.set_alpha(style.color().alpha as f32);
-        render_ctx.set_stroke_style(utils::Brush::SolidColor(self.color_change(&style.color())));
-
-        render_ctx.move_to(from.0 as f64, from.1 as f64);
-        render_ctx.line_to(to.0 as f64, to.1 as f64);
-
-        render_ctx.stroke();
+        self.prepare()?;
+        let points = [(from.0 as f64, from.1 as f64), (to.0 as f64, to.1 as f64)];
+        self.stroke_polyline(&mut self.render_ctx.borrow_mut(), &points, style);
 
         Ok(())
     }
@@ -105,19 +349,32 @@ impl<'a> DrawingBackend for OrbtkBackend<'a> {
         style: &S,
         fill: bool,
     ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.prepare()?;
         let mut render_ctx = self.render_ctx.borrow_mut();
         render_ctx.begin_path();
-        render_ctx.set_alpha(style.color().alpha as f32);
-        let width = (bottom_right.0 - upper_left.0) as f64;
-        let height = (bottom_right.1 - upper_left.1) as f64;
+        let (x, y) = self.to_physical(upper_left);
+        let width = (bottom_right.0 - upper_left.0) as f64 * self.scale;
+        let height = (bottom_right.1 - upper_left.1) as f64 * self.scale;
         if fill {
-            render_ctx.set_fill_style(utils::Brush::SolidColor(self.color_change(&style.color())));
-            render_ctx.fill_rect(upper_left.0 as f64, upper_left.1 as f64, width, height);
+            let color = self.color_change(&style.color());
+            let bounds = (x, y, x + width, y + height);
+            render_ctx.set_fill_style(self.gradient.brush(color, bounds));
+            render_ctx.fill_rect(x, y, width, height);
         } else {
-            render_ctx.set_line_width(style.stroke_width() as f64);
+            render_ctx.set_line_width(style.stroke_width() as f64 * self.scale);
             render_ctx
                 .set_stroke_style(utils::Brush::SolidColor(self.color_change(&style.color())));
-            render_ctx.stroke_rect(upper_left.0 as f64, upper_left.1 as f64, width, height);
+            if self.line_dash.is_solid() {
+                render_ctx.stroke_rect(x, y, width, height);
+            } else {
+                let (x0, y0) = (upper_left.0 as f64, upper_left.1 as f64);
+                let (x1, y1) = (bottom_right.0 as f64, bottom_right.1 as f64);
+                let points = [(x0, y0), (x1, y0), (x1, y1), (x0, y1), (x0, y0)];
+                for part in self.dashes(&points) {
+                    stroke::trace(&mut render_ctx, &part);
+                }
+                render_ctx.stroke();
+            }
         }
 
         Ok(())
@@ -128,22 +385,12 @@ impl<'a> DrawingBackend for OrbtkBackend<'a> {
         path: I,
         style: &S,
     ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
-        let mut render_ctx = self.render_ctx.borrow_mut();
-        render_ctx.begin_path();
-        render_ctx.set_line_width(style.stroke_width() as f64);
-        render_ctx.set_alpha(style.color().alpha as f32);
-        render_ctx.set_stroke_style(utils::Brush::SolidColor(self.color_change(&style.color())));
-
-        let iterator = path.into_iter();
-        for (index, point) in iterator.enumerate() {
-            if index == 0 {
-                render_ctx.move_to(point.0 as f64, point.1 as f64);
-            } else {
-                render_ctx.line_to(point.0 as f64, point.1 as f64);
-            }
-        }
-
-        render_ctx.stroke();
+        self.prepare()?;
+        let points: Vec<_> = path
+            .into_iter()
+            .map(|point| (point.0 as f64, point.1 as f64))
+            .collect();
+        self.stroke_polyline(&mut self.render_ctx.borrow_mut(), &points, style);
 
         Ok(())
     }
@@ -155,31 +402,31 @@ impl<'a> DrawingBackend for OrbtkBackend<'a> {
         style: &S,
         fill: bool,
     ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.prepare()?;
         let mut render_ctx = self.render_ctx.borrow_mut();
         render_ctx.begin_path();
-        render_ctx.set_alpha(style.color().alpha as f32);
+        let (x, y) = self.to_physical(center);
+        let r = radius as f64 * self.scale;
 
         if fill {
-            render_ctx.set_fill_style(utils::Brush::SolidColor(self.color_change(&style.color())));
-            render_ctx.arc(
-                center.0 as f64,
-                center.1 as f64,
-                radius as f64,
-                0.0,
-                std::f64::consts::PI * 2.0,
-            );
+            let color = self.color_change(&style.color());
+            let bounds = (x - r, y - r, x + r, y + r);
+            render_ctx.set_fill_style(self.gradient.brush(color, bounds));
+            render_ctx.arc(x, y, r, 0.0, std::f64::consts::PI * 2.0);
             render_ctx.fill();
         } else {
             render_ctx
                 .set_stroke_style(utils::Brush::SolidColor(self.color_change(&style.color())));
-            render_ctx.set_line_width(style.stroke_width() as f64);
-            render_ctx.arc(
-                center.0 as f64,
-                center.1 as f64,
-                radius as f64,
-                0.0,
-                std::f64::consts::PI * 2.0,
-            );
+            render_ctx.set_line_width(style.stroke_width() as f64 * self.scale);
+            if self.line_dash.is_solid() {
+                render_ctx.arc(x, y, r, 0.0, std::f64::consts::PI * 2.0);
+            } else {
+                let center = (center.0 as f64, center.1 as f64);
+                let points = stroke::circle_points(center, radius as f64);
+                for part in self.dashes(&points) {
+                    stroke::trace(&mut render_ctx, &part);
+                }
+            }
             render_ctx.stroke();
         }
 
@@ -191,15 +438,25 @@ impl<'a> DrawingBackend for OrbtkBackend<'a> {
         vert: I,
         style: &S,
     ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.prepare()?;
+        let points: Vec<_> = vert
+            .into_iter()
+            .map(|point| self.to_physical(point))
+            .collect();
+        let bounds = points.iter().fold(
+            (f64::MAX, f64::MAX, f64::MIN, f64::MIN),
+            |(x0, y0, x1, y1), &(x, y)| (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+        );
+
         let mut render_ctx = self.render_ctx.borrow_mut();
         render_ctx.begin_path();
-        render_ctx.set_alpha(style.color().alpha as f32);
-        render_ctx.set_fill_style(utils::Brush::SolidColor(self.color_change(&style.color())));
-        for (index, point) in vert.into_iter().enumerate() {
+        let color = self.color_change(&style.color());
+        render_ctx.set_fill_style(self.gradient.brush(color, bounds));
+        for (index, point) in points.iter().enumerate() {
             if index == 0 {
-                render_ctx.move_to(point.0 as f64, point.1 as f64);
+                render_ctx.move_to(point.0, point.1);
             } else {
-                render_ctx.line_to(point.0 as f64, point.1 as f64);
+                render_ctx.line_to(point.0, point.1);
             }
         }
         render_ctx.close_path();
@@ -214,8 +471,9 @@ impl<'a> DrawingBackend for OrbtkBackend<'a> {
         style: &TStyle,
         pos: (i32, i32),
     ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.prepare()?;
+        let layout = self.layout_text(text, style)?;
         let mut render_ctx = self.render_ctx.borrow_mut();
-        let (mut x, mut y) = (pos.0, pos.1);
         let degree = match style.transform() {
             FontTransform::None => 0.0_f64,
             FontTransform::Rotate90 => 90.0_f64,
@@ -224,61 +482,205 @@ impl<'a> DrawingBackend for OrbtkBackend<'a> {
         }
         .to_radians();
 
-        if degree != 0.0 {
-            render_ctx.save();
-            render_ctx.set_transform(
-                degree.cos(),
-                degree.sin(),
-                -degree.sin(),
-                degree.cos(),
-                x as f64,
-                y as f64,
-            );
-            x = 0;
-            y = 0;
-        }
-
-        render_ctx.set_font_size(style.size());
-        render_ctx.set_font_family("Roboto-Regular");
+        // Move the origin to `pos` and rotate around it, on top of the caller's transform.
+        let (x, y) = self.to_physical(pos);
+        let (x, y) = if degree != 0.0 {
+            self.transform
+                .then(Transform([
+                    degree.cos(),
+                    degree.sin(),
+                    -degree.sin(),
+                    degree.cos(),
+                    x,
+                    y,
+                ]))
+                .apply(&mut render_ctx);
+            (0.0, 0.0)
+        } else {
+            (x, y)
+        };
 
-        let metrics = render_ctx.measure_text(text);
+        render_ctx.set_font_size(layout.size);
 
-        let dx = match style.anchor().h_pos {
+        // The block of lines is anchored as a whole, lines are aligned within it the same way.
+        let align = match style.anchor().h_pos {
             HPos::Left => 0.0,
-            HPos::Right => -metrics.width,
-            HPos::Center => -metrics.width / 2.0,
+            HPos::Right => 1.0,
+            HPos::Center => 0.5,
         };
+        // Anchor the line boxes from ascender to descender rather than the measured ink, so
+        // labels share a baseline whatever characters they contain. OrbTk places the top of the
+        // line at the given position.
         let dy = match style.anchor().v_pos {
             VPos::Top => 0.0,
-            VPos::Center => -metrics.height / 2.0,
-            VPos::Bottom => -metrics.height,
+            VPos::Center => -layout.height / 2.0,
+            VPos::Bottom => -layout.height,
         };
 
         render_ctx.begin_path();
-        render_ctx.set_alpha(style.color().alpha as f32);
         render_ctx.set_fill_style(utils::Brush::SolidColor(self.color_change(&style.color())));
 
-        if degree != 0.0 {
-            render_ctx.fill_text(text, 0.0, 0.0);
-        } else {
-            render_ctx.fill_text(text, f64::from(x) + dx, f64::from(y) + dy);
+        // For rotated text the origin was moved to `pos`, so the anchor offsets are applied in
+        // the rotated frame, the same way plotters lays out rotated text on its other backends.
+        let ascent = self.fonts.metrics(layout.font, layout.size).ascent;
+        for (i, line) in layout.lines.iter().enumerate() {
+            let mut run_x = x - line.width * align;
+            let line_y = y + dy + i as f64 * layout.line_advance;
+            for &(run, font, width) in line.runs.iter() {
+                // Runs in fallback fonts sit on the baseline of the text's own font.
+                let shift = ascent - self.fonts.metrics(font, layout.size).ascent;
+                render_ctx.set_font_family(font);
+                render_ctx.fill_text(run, run_x, line_y + shift);
+                run_x += width;
+            }
         }
 
         if degree != 0.0 {
-            render_ctx.restore();
-            render_ctx.set_transform(1.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+            self.transform.apply(&mut render_ctx);
         }
 
         Ok(())
     }
 
+    fn blit_bitmap<'b>(
+        &mut self,
+        pos: BackendCoord,
+        (iw, ih): (u32, u32),
+        src: &'b [u8],
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.prepare()?;
+        // Clip the image rectangle against the backend size, everything outside is dropped. The
+        // bounds are computed in i64 as a large bitmap far off the canvas overflows i32.
+        let x0 = (pos.0 as i64).max(0);
+        let y0 = (pos.1 as i64).max(0);
+        let x1 = (pos.0 as i64 + iw as i64).min(self.width as i64);
+        let y1 = (pos.1 as i64 + ih as i64).min(self.height as i64);
+        let needed = (iw as usize)
+            .checked_mul(ih as usize)
+            .and_then(|pixels| pixels.checked_mul(3));
+        if needed.map_or(true, |needed| src.len() < needed) {
+            return Err(DrawingErrorKind::DrawingError(OrbtkError::InvalidBitmap {
+                width: iw,
+                height: ih,
+                len: src.len(),
+            }));
+        }
+        if x0 >= x1 || y0 >= y1 {
+            return Ok(());
+        }
+
+        // Scale the visible part to the physical pixels it covers.
+        let (w, h) = ((x1 - x0) as u32, (y1 - y0) as u32);
+        let offset = ((x0 - pos.0 as i64) as u32, (y0 - pos.1 as i64) as u32);
+        // Clipped to the backend size, so the corners fit into i32 again.
+        let (x0, y0, x1, y1) = (x0 as i32, y0 as i32, x1 as i32, y1 as i32);
+        let (px0, py0) = self.to_physical((x0, y0));
+        let (px1, py1) = self.to_physical((x1, y1));
+        let (px0, py0) = (px0.round() as u32, py0.round() as u32);
+        let dst = (
+            (px1.round() as u32).saturating_sub(px0).max(1),
+            (py1.round() as u32).saturating_sub(py0).max(1),
+        );
+        let data = rgb_to_argb(src, iw, offset, (w, h), dst);
+
+        pixels::draw_argb(
+            self.render_ctx.get_mut(),
+            (px0, py0),
+            dst,
+            data,
+            "blit_bitmap",
+        )
+        .map_err(DrawingErrorKind::DrawingError)?;
+
+        Ok(())
+    }
+
     fn estimate_text_size<TStyle: BackendTextStyle>(
         &self,
         text: &str,
         style: &TStyle,
     ) -> Result<(u32, u32), DrawingErrorKind<Self::ErrorType>> {
-        let mut render_ctx = self.render_ctx.borrow_mut();
-        let text_metrics = render_ctx.measure(text, style.size(), "Roboto-Regular");
-        Ok((text_metrics.width as u32, text_metrics.height as u32))
+        // Measured at the physical size text is drawn with, reported in logical pixels.
+        let layout = self.layout_text(text, style)?;
+        Ok((
+            (layout.width / self.scale).ceil() as u32,
+            (layout.height / self.scale).ceil() as u32,
+        ))
+    }
+}
+
+impl<'a> Drop for OrbtkBackend<'a> {
+    fn drop(&mut self) {
+        // Errors can't be reported from here, call `present` to see them.
+        let _ = self.flush_pixels();
+        // Leave the render context unclipped, the way it was handed to the backend.
+        if self.applied_clip.is_some() {
+            self.render_ctx.get_mut().restore();
+        }
+    }
+}
+
+/// A 2D affine transform in the `(a, b, c, d, e, f)` layout of `RenderContext2D::set_transform`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Transform([f64; 6]);
+
+impl Transform {
+    const IDENTITY: Transform = Transform([1.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+
+    /// Returns the transform that applies `inner` first and `self` afterwards.
+    fn then(self, inner: Transform) -> Transform {
+        let [a, b, c, d, e, f] = self.0;
+        let [ia, ib, ic, id, ie, if_] = inner.0;
+        Transform([
+            a * ia + c * ib,
+            b * ia + d * ib,
+            a * ic + c * id,
+            b * ic + d * id,
+            a * ie + c * if_ + e,
+            b * ie + d * if_ + f,
+        ])
+    }
+
+    fn apply(self, render_ctx: &mut RenderContext2D) {
+        let [a, b, c, d, e, f] = self.0;
+        render_ctx.set_transform(a, b, c, d, e, f);
+    }
+}
+
+/// Converts a plotters color into an OrbTk color, with the alpha scaled to a byte.
+pub(crate) fn to_rgba(color: &BackendColor) -> Color {
+    Color::rgba(
+        color.rgb.0,
+        color.rgb.1,
+        color.rgb.2,
+        (color.alpha.clamp(0.0, 1.0) * 255.0).round() as u8,
+    )
+}
+
+/// Converts the `(w, h)` region at `offset` of a tightly packed RGB buffer with `stride` pixels
+/// per row into opaque ARGB pixels of size `dst`, using nearest neighbour sampling if the sizes
+/// differ.
+fn rgb_to_argb(
+    src: &[u8],
+    stride: u32,
+    offset: (u32, u32),
+    (w, h): (u32, u32),
+    dst: (u32, u32),
+) -> Vec<u32> {
+    let mut data = Vec::with_capacity((dst.0 * dst.1) as usize);
+    for dy in 0..dst.1 {
+        let sy = offset.1 + dy * h / dst.1;
+        for dx in 0..dst.0 {
+            let sx = offset.0 + dx * w / dst.0;
+            let idx = (sy as usize * stride as usize + sx as usize) * 3;
+            let pixel = match src.get(idx..idx + 3) {
+                Some(rgb) => {
+                    0xff00_0000 | (rgb[0] as u32) << 16 | (rgb[1] as u32) << 8 | rgb[2] as u32
+                }
+                None => 0,
+            };
+            data.push(pixel);
+        }
     }
+    data
 }
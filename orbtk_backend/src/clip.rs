@@ -0,0 +1,87 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use plotters_backend::BackendCoord;
+
+/// A stack of clip rectangles shared between an [`OrbtkBackend`](crate::OrbtkBackend) or a
+/// [`RecordingBackend`](crate::RecordingBackend) and the code building the chart.
+///
+/// Once the backend is turned into a plotters drawing area it can't be reached anymore, so the
+/// clip is changed through this handle instead. Primitives are clipped to the intersection of
+/// all rectangles on the stack, starting with the next primitive drawn.
+///
+/// Charts aren't clipped on their own: this crate only depends on `plotters-backend`, which
+/// knows nothing about charts, so there is no helper taking a `ChartContext`. Push the pixel
+/// range of the plotting area by hand with [`push_range`](ClipStack::push_range) after the
+/// chart is built, and push it again if the chart is rebuilt with a different layout.
+#[derive(Debug, Clone, Default)]
+pub struct ClipStack {
+    rects: Rc<RefCell<Vec<(BackendCoord, BackendCoord)>>>,
+}
+
+impl ClipStack {
+    /// Pushes the rectangle spanned by `upper_left` and `bottom_right` in backend coordinates.
+    ///
+    /// The rectangle is removed again when the returned guard is dropped, together with any
+    /// rectangle pushed after it.
+    pub fn push(&self, upper_left: BackendCoord, bottom_right: BackendCoord) -> ClipGuard {
+        let mut rects = self.rects.borrow_mut();
+        let depth = rects.len();
+        rects.push((upper_left, bottom_right));
+        ClipGuard {
+            stack: self.clone(),
+            depth,
+        }
+    }
+
+    /// Pushes the pixel range of a plotters drawing area, as returned by
+    /// `DrawingArea::get_pixel_range`.
+    ///
+    /// ```ignore
+    /// let _plot_clip = clip.push_range(chart.plotting_area().get_pixel_range());
+    /// chart.draw_series(LineSeries::new(data, &RED))?;
+    /// ```
+    pub fn push_range(&self, (x, y): (std::ops::Range<i32>, std::ops::Range<i32>)) -> ClipGuard {
+        self.push((x.start, y.start), (x.end, y.end))
+    }
+
+    /// Removes all rectangles, primitives are no longer clipped.
+    pub fn clear(&self) {
+        self.rects.borrow_mut().clear();
+    }
+
+    /// The intersection of all rectangles on the stack, `None` if the stack is empty.
+    pub fn effective(&self) -> Option<(BackendCoord, BackendCoord)> {
+        let mut result: Option<(BackendCoord, BackendCoord)> = None;
+        for &(a, b) in self.rects.borrow().iter() {
+            let mut upper_left = (a.0.min(b.0), a.1.min(b.1));
+            let mut bottom_right = (a.0.max(b.0), a.1.max(b.1));
+            if let Some((ul, br)) = result {
+                upper_left = (upper_left.0.max(ul.0), upper_left.1.max(ul.1));
+                // Rectangles that don't overlap leave an empty clip.
+                bottom_right = (
+                    bottom_right.0.min(br.0).max(upper_left.0),
+                    bottom_right.1.min(br.1).max(upper_left.1),
+                );
+            }
+            result = Some((upper_left, bottom_right));
+        }
+        result
+    }
+}
+
+/// Keeps a rectangle on a [`ClipStack`] until it is dropped, so an early return doesn't leave
+/// the following primitives clipped.
+#[must_use = "the rectangle is popped again as soon as the guard is dropped"]
+#[derive(Debug)]
+pub struct ClipGuard {
+    stack: ClipStack,
+    /// Length of the stack before the rectangle was pushed.
+    depth: usize,
+}
+
+impl Drop for ClipGuard {
+    fn drop(&mut self) {
+        self.stack.rects.borrow_mut().truncate(self.depth);
+    }
+}
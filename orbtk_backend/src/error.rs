@@ -0,0 +1,65 @@
+/// Errors reported by [`OrbtkBackend`](crate::OrbtkBackend).
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrbtkError {
+    /// A font could not be registered with the render context.
+    FontRegistration { family: String, reason: String },
+    /// Text was drawn or measured without any font registered, which can only happen without
+    /// the `bundled-font` feature.
+    NoFont,
+    /// The backend was created with a size that can't be drawn to.
+    InvalidTarget { width: f64, height: f64 },
+    /// The backend was created with a scale factor that isn't positive.
+    InvalidScaleFactor { scale: f64 },
+    /// A coordinate or size handed to the backend was NaN or infinite.
+    NonFiniteCoordinate { context: &'static str, value: f64 },
+    /// A bitmap buffer is too small for the dimensions it claims to have.
+    InvalidBitmap { width: u32, height: u32, len: usize },
+    /// Writing a rendered chart to a file failed.
+    Export { path: String, reason: String },
+    /// The operation can't be performed by the OrbTk render context.
+    Unsupported {
+        operation: &'static str,
+        reason: String,
+    },
+}
+
+impl std::fmt::Display for OrbtkError {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            OrbtkError::FontRegistration { family, reason } => {
+                write!(fmt, "failed to register font '{}': {}", family, reason)
+            }
+            OrbtkError::NoFont => write!(
+                fmt,
+                "no font registered, register one with `register_font` or enable the \
+                 `bundled-font` feature"
+            ),
+            OrbtkError::InvalidTarget { width, height } => {
+                write!(fmt, "invalid drawing target size {}x{}", width, height)
+            }
+            OrbtkError::InvalidScaleFactor { scale } => {
+                write!(fmt, "invalid scale factor {}, it has to be positive", scale)
+            }
+            OrbtkError::NonFiniteCoordinate { context, value } => {
+                write!(fmt, "non-finite value {} for {}", value, context)
+            }
+            OrbtkError::InvalidBitmap { width, height, len } => write!(
+                fmt,
+                "bitmap of {}x{} pixels needs {} bytes, got {}",
+                width,
+                height,
+                // Can't overflow, unlike the u32 product.
+                *width as u128 * *height as u128 * 3,
+                len
+            ),
+            OrbtkError::Export { path, reason } => {
+                write!(fmt, "failed to export to '{}': {}", path, reason)
+            }
+            OrbtkError::Unsupported { operation, reason } => {
+                write!(fmt, "unsupported operation {}: {}", operation, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for OrbtkError {}
@@ -0,0 +1,455 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use orbtk::prelude::*;
+use plotters_backend::{FontFamily, FontStyle};
+
+use crate::OrbtkError;
+
+/// Name under which the bundled Roboto font is registered in the render context.
+#[cfg(feature = "bundled-font")]
+pub const DEFAULT_FONT: &str = "Roboto-Regular";
+
+#[cfg(feature = "bundled-font")]
+const DEFAULT_FONT_DATA: &[u8] = include_bytes!("Roboto-Regular.ttf");
+
+/// Fonts registered with [`register_font`], shared by all backends created afterwards.
+static SHARED_FONTS: Mutex<Option<FontRegistry>> = Mutex::new(None);
+
+static REGISTRATIONS: AtomicU64 = AtomicU64::new(0);
+
+/// Returns how often font data was registered in a render context, which makes OrbTk parse it.
+///
+/// Backends created for a render context that already has the fonts don't register them again.
+pub fn font_registrations() -> u64 {
+    REGISTRATIONS.load(Ordering::Relaxed)
+}
+
+/// Registers a font for all backends created from now on, mapping `family` in `style` to it.
+///
+/// The font data is checked once here. Every render context a backend is created for gets the
+/// font registered on first use only, so creating a backend per frame doesn't parse fonts again.
+pub fn register_font(
+    family: FontFamily,
+    style: FontStyle,
+    name: &str,
+    bytes: &'static [u8],
+) -> Result<(), OrbtkError> {
+    let mut scratch = RenderContext2D::new(1.0, 1.0);
+    let mut shared = SHARED_FONTS.lock().unwrap_or_else(|e| e.into_inner());
+    shared.get_or_insert_with(FontRegistry::default).register(
+        &mut scratch,
+        family,
+        style,
+        name,
+        bytes,
+    )
+}
+
+/// Adds a font to the glyph fallback chain of all backends created from now on.
+///
+/// Characters missing from a label's font are drawn with the first font of the chain that has
+/// them, e.g. a CJK font for Japanese axis labels. Fonts are tried in the order they were added.
+pub fn register_fallback_font(name: &str, bytes: &'static [u8]) -> Result<(), OrbtkError> {
+    let mut scratch = RenderContext2D::new(1.0, 1.0);
+    let mut shared = SHARED_FONTS.lock().unwrap_or_else(|e| e.into_inner());
+    shared
+        .get_or_insert_with(FontRegistry::default)
+        .add_glyph_fallback(&mut scratch, name, bytes)
+}
+
+/// Returns the shared fonts, with all of them registered in `render_ctx`.
+pub(crate) fn shared_fonts(render_ctx: &mut RenderContext2D) -> Result<FontRegistry, OrbtkError> {
+    let fonts = SHARED_FONTS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get_or_insert_with(FontRegistry::default)
+        .clone();
+    fonts.prepare(render_ctx)?;
+    Ok(fonts)
+}
+
+/// Vertical metrics of a font at a given size, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FontMetrics {
+    /// Distance from the top of the line to the baseline.
+    pub ascent: f64,
+    /// Distance from the baseline to the bottom of the line.
+    pub descent: f64,
+    /// Extra space the font asks for between the bottom of a line and the top of the next.
+    pub line_gap: f64,
+}
+
+impl FontMetrics {
+    /// Height of a line of text, the box plotters anchors text with.
+    pub fn height(&self) -> f64 {
+        self.ascent + self.descent
+    }
+
+    /// Height of a block of `lines` lines of text.
+    pub fn block_height(&self, lines: usize) -> f64 {
+        lines as f64 * self.height() + lines.saturating_sub(1) as f64 * self.line_gap
+    }
+}
+
+/// Maps plotters font families and styles to fonts registered in an OrbTk `RenderContext2D`.
+///
+/// A style without its own face falls back to the normal face of the family, italic and oblique
+/// stand in for each other. Families without a registered font resolve to the fallback font,
+/// which is the bundled Roboto unless changed with [`set_fallback`](FontRegistry::set_fallback).
+/// Without the `bundled-font` feature the registry starts out empty and the first registered
+/// font becomes the fallback.
+///
+/// Characters the resolved font has no glyph for are taken from the fonts added with
+/// [`add_glyph_fallback`](FontRegistry::add_glyph_fallback), see [`runs`](FontRegistry::runs).
+#[derive(Debug, Clone)]
+pub struct FontRegistry {
+    families: HashMap<(String, &'static str), String>,
+    /// Font data by name, to register it in render contexts.
+    data: Vec<(String, &'static [u8])>,
+    fallback: String,
+    /// Fonts tried in order for characters the resolved font doesn't have.
+    glyph_fallbacks: Vec<String>,
+}
+
+#[cfg(feature = "bundled-font")]
+impl Default for FontRegistry {
+    fn default() -> Self {
+        let mut families = HashMap::new();
+        families.insert(
+            (DEFAULT_FONT.to_string(), FontStyle::Normal.as_str()),
+            DEFAULT_FONT.to_string(),
+        );
+        Self {
+            families,
+            data: vec![(DEFAULT_FONT.to_string(), DEFAULT_FONT_DATA)],
+            fallback: DEFAULT_FONT.to_string(),
+            glyph_fallbacks: Vec::new(),
+        }
+    }
+}
+
+#[cfg(not(feature = "bundled-font"))]
+impl Default for FontRegistry {
+    fn default() -> Self {
+        Self {
+            families: HashMap::new(),
+            data: Vec::new(),
+            fallback: String::new(),
+            glyph_fallbacks: Vec::new(),
+        }
+    }
+}
+
+impl FontRegistry {
+    /// Registers `bytes` under `name` in the render context and maps `family` in `style` to it.
+    ///
+    /// The font is also reachable as `FontFamily::Name(name)` in the same style.
+    pub fn register(
+        &mut self,
+        render_ctx: &mut RenderContext2D,
+        family: FontFamily,
+        style: FontStyle,
+        name: &str,
+        bytes: &'static [u8],
+    ) -> Result<(), OrbtkError> {
+        register_checked(render_ctx, name, bytes)?;
+        self.data.retain(|(registered, _)| registered != name);
+        self.data.push((name.to_string(), bytes));
+        self.map_family(FontFamily::Name(name), style, name);
+        self.map_family(family, style, name);
+        if self.fallback.is_empty() {
+            self.fallback = name.to_string();
+        }
+        Ok(())
+    }
+
+    /// Registers `bytes` under `name` in the render context and appends it to the fonts tried
+    /// for characters that the font of a text doesn't have.
+    pub fn add_glyph_fallback(
+        &mut self,
+        render_ctx: &mut RenderContext2D,
+        name: &str,
+        bytes: &'static [u8],
+    ) -> Result<(), OrbtkError> {
+        register_checked(render_ctx, name, bytes)?;
+        self.data.retain(|(registered, _)| registered != name);
+        self.data.push((name.to_string(), bytes));
+        self.glyph_fallbacks.retain(|registered| registered != name);
+        self.glyph_fallbacks.push(name.to_string());
+        Ok(())
+    }
+
+    /// Registers all fonts of the registry in `render_ctx` that it doesn't know yet.
+    pub fn prepare(&self, render_ctx: &mut RenderContext2D) -> Result<(), OrbtkError> {
+        for (name, bytes) in &self.data {
+            if !is_registered(render_ctx, name) {
+                register_checked(render_ctx, name, bytes)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Maps `family` in `style` to a font that is already registered under `name`.
+    pub fn map_family(&mut self, family: FontFamily, style: FontStyle, name: &str) {
+        self.families.insert(
+            (family.as_str().to_string(), style.as_str()),
+            name.to_string(),
+        );
+    }
+
+    /// Sets the font used for families that have no registered font.
+    pub fn set_fallback(&mut self, name: &str) {
+        self.fallback = name.to_string();
+    }
+
+    /// Returns the vertical metrics of the registered font `name` at `size`.
+    ///
+    /// OrbTk scales fonts so that a line from ascender to descender is `size` tall, the font only
+    /// decides where the baseline lies within it. Fonts registered in the render context without
+    /// the registry, e.g. through [`map_family`](FontRegistry::map_family), are assumed to have
+    /// the proportions of Roboto.
+    pub fn metrics(&self, name: &str, size: f64) -> FontMetrics {
+        let (ascent, line_gap) = self
+            .data
+            .iter()
+            .find(|(registered, _)| registered == name)
+            .and_then(|(_, bytes)| line_proportions(bytes))
+            .unwrap_or(ROBOTO_PROPORTIONS);
+        FontMetrics {
+            ascent: size * ascent,
+            descent: size * (1.0 - ascent),
+            line_gap: size * line_gap,
+        }
+    }
+
+    /// Splits `text` into runs of characters that are drawn with the same font.
+    ///
+    /// Each character is drawn with `font` if it has a glyph for it, otherwise with the first
+    /// glyph fallback that has one. Characters no font has stay with `font`, which then shows
+    /// its missing glyph box. Fonts registered without data are assumed to have every glyph.
+    pub fn runs<'t, 's>(&'s self, text: &'t str, font: &'s str) -> Vec<(&'t str, &'s str)> {
+        if self.glyph_fallbacks.is_empty() {
+            // Nothing to fall back to, so the font's coverage doesn't matter.
+            return match text {
+                "" => vec![],
+                text => vec![(text, font)],
+            };
+        }
+        let mut runs: Vec<(&'t str, &'s str)> = Vec::new();
+        let mut start = 0;
+        for (pos, c) in text.char_indices() {
+            let chosen = if self.has_glyph(font, c) {
+                font
+            } else {
+                self.glyph_fallbacks
+                    .iter()
+                    .map(String::as_str)
+                    .find(|fallback| self.has_glyph(fallback, c))
+                    .unwrap_or(font)
+            };
+            match runs.last_mut() {
+                Some((run, run_font)) if *run_font == chosen => {
+                    *run = &text[start..pos + c.len_utf8()]
+                }
+                _ => {
+                    start = pos;
+                    runs.push((&text[pos..pos + c.len_utf8()], chosen));
+                }
+            }
+        }
+        runs
+    }
+
+    /// `font` followed by the glyph fallbacks, the fonts [`runs`](FontRegistry::runs) chooses
+    /// from.
+    pub(crate) fn glyph_chain<'s>(&'s self, font: &'s str) -> Vec<&'s str> {
+        std::iter::once(font)
+            .chain(self.glyph_fallbacks.iter().map(String::as_str))
+            .collect()
+    }
+
+    /// Identifies the fonts of `chain` by their name and the address and length of their data,
+    /// as different data may be registered under the same name in different registries. Fonts
+    /// registered without data are identified by their name alone.
+    pub(crate) fn chain_identity(&self, chain: &[&str]) -> String {
+        chain
+            .iter()
+            .map(|&name| {
+                let data = self.data.iter().find(|(registered, _)| registered == name);
+                match data {
+                    Some((_, bytes)) => format!("{}@{:p}+{}", name, bytes.as_ptr(), bytes.len()),
+                    None => name.to_string(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn has_glyph(&self, name: &str, c: char) -> bool {
+        match self.data.iter().find(|(registered, _)| registered == name) {
+            Some((_, bytes)) => c.is_control() || glyph_index(bytes, c).unwrap_or(0) != 0,
+            None => true,
+        }
+    }
+
+    /// Like [`resolve`](FontRegistry::resolve), but fails if the registry has no font at all.
+    pub(crate) fn resolve_checked(
+        &self,
+        family: FontFamily,
+        style: FontStyle,
+    ) -> Result<&str, OrbtkError> {
+        match self.resolve(family, style) {
+            "" => Err(OrbtkError::NoFont),
+            font => Ok(font),
+        }
+    }
+
+    /// Returns the OrbTk font name to use for `family` in `style`.
+    pub fn resolve(&self, family: FontFamily, style: FontStyle) -> &str {
+        let candidates = match style {
+            FontStyle::Italic => [FontStyle::Italic, FontStyle::Oblique, FontStyle::Normal],
+            FontStyle::Oblique => [FontStyle::Oblique, FontStyle::Italic, FontStyle::Normal],
+            FontStyle::Bold => [FontStyle::Bold, FontStyle::Normal, FontStyle::Normal],
+            FontStyle::Normal => [FontStyle::Normal, FontStyle::Normal, FontStyle::Normal],
+        };
+        let family = family.as_str();
+        candidates
+            .iter()
+            .find_map(|style| self.families.get(&(family.to_string(), style.as_str())))
+            .unwrap_or(&self.fallback)
+    }
+}
+
+/// Shares of the line height above the baseline and between lines in Roboto, which has an
+/// ascender of 1900, a descender of -500 and no line gap.
+const ROBOTO_PROPORTIONS: (f64, f64) = (1900.0 / 2400.0, 0.0);
+
+fn u16_at(bytes: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_be_bytes([*bytes.get(pos)?, *bytes.get(pos + 1)?]))
+}
+
+fn u32_at(bytes: &[u8], pos: usize) -> Option<u32> {
+    Some((u16_at(bytes, pos)? as u32) << 16 | u16_at(bytes, pos + 2)? as u32)
+}
+
+/// Returns the offset of the table `tag` in a TrueType or OpenType font.
+fn table(bytes: &[u8], tag: &[u8; 4]) -> Option<usize> {
+    let record = (0..u16_at(bytes, 4)? as usize)
+        .map(|table| 12 + 16 * table)
+        .find(|&record| bytes.get(record..record + 4) == Some(&tag[..]))?;
+    Some(u32_at(bytes, record + 8)? as usize)
+}
+
+/// Looks up the glyph of `c` in the Unicode subtables of the `cmap` table of a font. Glyph 0 is
+/// the missing glyph box.
+fn glyph_index(bytes: &[u8], c: char) -> Option<u32> {
+    let cmap = table(bytes, b"cmap")?;
+    let c = c as u32;
+    let mut found = None;
+    for record in (0..u16_at(bytes, cmap + 2)? as usize).map(|i| cmap + 4 + 8 * i) {
+        let platform = u16_at(bytes, record)?;
+        let encoding = u16_at(bytes, record + 2)?;
+        // Unicode, or Windows with the Unicode BMP or full repertoire encodings.
+        if !(platform == 0 || (platform == 3 && (encoding == 1 || encoding == 10))) {
+            continue;
+        }
+        let subtable = cmap + u32_at(bytes, record + 4)? as usize;
+        let glyph = match u16_at(bytes, subtable)? {
+            4 => cmap_format_4(bytes, subtable, c),
+            12 => cmap_format_12(bytes, subtable, c),
+            _ => None,
+        };
+        match glyph {
+            Some(0) => found = Some(0),
+            Some(glyph) => return Some(glyph),
+            None => {}
+        }
+    }
+    found
+}
+
+/// Segment mapping to delta values, which only covers the Basic Multilingual Plane.
+fn cmap_format_4(bytes: &[u8], subtable: usize, c: u32) -> Option<u32> {
+    if c > 0xffff {
+        return None;
+    }
+    let seg_count_x2 = u16_at(bytes, subtable + 6)? as usize;
+    let end_codes = subtable + 14;
+    let start_codes = end_codes + seg_count_x2 + 2;
+    let id_deltas = start_codes + seg_count_x2;
+    let id_range_offsets = id_deltas + seg_count_x2;
+    for segment in (0..seg_count_x2).step_by(2) {
+        if (u16_at(bytes, end_codes + segment)? as u32) < c {
+            continue;
+        }
+        let start = u16_at(bytes, start_codes + segment)? as u32;
+        if start > c {
+            return Some(0);
+        }
+        let delta = u16_at(bytes, id_deltas + segment)? as u32;
+        let range_offset = u16_at(bytes, id_range_offsets + segment)? as usize;
+        if range_offset == 0 {
+            return Some((c + delta) & 0xffff);
+        }
+        let pos = id_range_offsets + segment + range_offset + 2 * (c - start) as usize;
+        let glyph = u16_at(bytes, pos)? as u32;
+        return Some(if glyph == 0 {
+            0
+        } else {
+            (glyph + delta) & 0xffff
+        });
+    }
+    Some(0)
+}
+
+/// Segmented coverage, which covers all of Unicode.
+fn cmap_format_12(bytes: &[u8], subtable: usize, c: u32) -> Option<u32> {
+    let groups = u32_at(bytes, subtable + 12)? as usize;
+    for group in (0..groups).map(|i| subtable + 16 + 12 * i) {
+        let start = u32_at(bytes, group)?;
+        let end = u32_at(bytes, group + 4)?;
+        if (start..=end).contains(&c) {
+            return Some(u32_at(bytes, group + 8)? + c - start);
+        }
+    }
+    Some(0)
+}
+
+/// Reads the shares of the line height above the baseline and between lines from the `hhea`
+/// table of a TrueType or OpenType font.
+fn line_proportions(bytes: &[u8]) -> Option<(f64, f64)> {
+    let hhea = table(bytes, b"hhea")?;
+    let ascender = u16_at(bytes, hhea + 4)? as i16 as f64;
+    let descender = u16_at(bytes, hhea + 6)? as i16 as f64;
+    let line_gap = u16_at(bytes, hhea + 8)? as i16 as f64;
+    let height = ascender - descender;
+    if height <= 0.0 {
+        return None;
+    }
+    Some((ascender / height, line_gap.max(0.0) / height))
+}
+
+/// `RenderContext2D` can't list its fonts, but only measures text with fonts it knows.
+fn is_registered(render_ctx: &mut RenderContext2D, name: &str) -> bool {
+    render_ctx.measure("M", 12.0, name).width > 0.0
+}
+
+/// Registers a font and verifies that OrbTk could actually parse it, as `register_font` silently
+/// ignores invalid data.
+pub(crate) fn register_checked(
+    render_ctx: &mut RenderContext2D,
+    name: &str,
+    bytes: &'static [u8],
+) -> Result<(), OrbtkError> {
+    render_ctx.register_font(name, bytes);
+    REGISTRATIONS.fetch_add(1, Ordering::Relaxed);
+    if !is_registered(render_ctx, name) {
+        return Err(OrbtkError::FontRegistration {
+            family: name.to_string(),
+            reason: "the font data could not be parsed".to_string(),
+        });
+    }
+    Ok(())
+}
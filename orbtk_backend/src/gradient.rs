@@ -0,0 +1,146 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use orbtk::prelude::*;
+use plotters_backend::BackendColor;
+
+use crate::backend;
+
+/// A gradient in coordinates relative to the bounding box of the filled element, `(0, 0)` being
+/// its upper left and `(1, 1)` its bottom right corner.
+#[derive(Debug, Clone)]
+pub enum Gradient {
+    Linear {
+        start: (f64, f64),
+        end: (f64, f64),
+        /// Positions between 0 and 1 along the gradient and their colors.
+        stops: Vec<(f64, BackendColor)>,
+    },
+    Radial {
+        center: (f64, f64),
+        /// Radius relative to the larger side of the bounding box.
+        radius: f64,
+        /// Positions between 0 and 1 from the center outwards and their colors.
+        stops: Vec<(f64, BackendColor)>,
+    },
+}
+
+impl Gradient {
+    /// A linear gradient from `top` at the top of the element to `bottom` at its bottom.
+    pub fn vertical(top: BackendColor, bottom: BackendColor) -> Self {
+        Gradient::Linear {
+            start: (0.5, 0.0),
+            end: (0.5, 1.0),
+            stops: vec![(0.0, top), (1.0, bottom)],
+        }
+    }
+
+    /// A linear gradient from `left` at the left of the element to `right` at its right.
+    pub fn horizontal(left: BackendColor, right: BackendColor) -> Self {
+        Gradient::Linear {
+            start: (0.0, 0.5),
+            end: (1.0, 0.5),
+            stops: vec![(0.0, left), (1.0, right)],
+        }
+    }
+
+    /// A radial gradient from `inner` at the center of the element to `outer` at its edges.
+    pub fn radial(inner: BackendColor, outer: BackendColor) -> Self {
+        Gradient::Radial {
+            center: (0.5, 0.5),
+            radius: 0.5,
+            stops: vec![(0.0, inner), (1.0, outer)],
+        }
+    }
+
+    /// Builds the OrbTk brush for an element whose bounding box in physical pixels is
+    /// `(x0, y0, x1, y1)`.
+    pub(crate) fn to_brush(&self, (x0, y0, x1, y1): (f64, f64, f64, f64)) -> utils::Brush {
+        let (w, h) = (x1 - x0, y1 - y0);
+        let point = |(rx, ry): (f64, f64)| Point::new(x0 + rx * w, y0 + ry * h);
+        let to_stops = |stops: &[(f64, BackendColor)]| {
+            stops
+                .iter()
+                .map(|(pos, color)| utils::GradientStop {
+                    pos: Some(utils::OnLinePos::new(
+                        pos.clamp(0.0, 1.0) * 100.0,
+                        utils::OnLinePosKind::Percentage,
+                    )),
+                    color: backend::to_rgba(color),
+                })
+                .collect()
+        };
+
+        let (kind, stops) = match self {
+            Gradient::Linear { start, end, stops } => (
+                utils::GradientKind::Linear(utils::LinearGradientCoords::Ends {
+                    start: point(*start),
+                    end: point(*end),
+                }),
+                to_stops(stops),
+            ),
+            Gradient::Radial {
+                center,
+                radius,
+                stops,
+            } => (
+                utils::GradientKind::Radial(utils::RadialGradient {
+                    size: utils::RadialGradientSize::Radius(radius * w.max(h)),
+                    position: Some(point(*center)),
+                }),
+                to_stops(stops),
+            ),
+        };
+        utils::Brush::Gradient(utils::Gradient {
+            kind,
+            stops,
+            repeat: false,
+        })
+    }
+}
+
+/// A gradient shared between an [`OrbtkBackend`](crate::OrbtkBackend) or a
+/// [`RecordingBackend`](crate::RecordingBackend) and the code building the chart, used instead of
+/// the solid color of filled rectangles, circles and polygons.
+///
+/// Like [`ClipStack`](crate::ClipStack) it stays usable after the backend was moved into a
+/// plotters drawing area, so it can be set around single series:
+///
+/// ```ignore
+/// let gradient = backend.gradient_fill();
+/// let root = backend.into_drawing_area();
+/// // ...
+/// gradient.set(Gradient::vertical(RED.to_backend_color(), WHITE.to_backend_color()));
+/// chart.draw_series(Histogram::vertical(&chart).style(RED.filled()).data(data))?;
+/// gradient.clear();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct GradientFill {
+    gradient: Rc<RefCell<Option<Gradient>>>,
+}
+
+impl GradientFill {
+    /// Fills the following elements with `gradient`, mapped to each element's bounding box.
+    pub fn set(&self, gradient: Gradient) {
+        *self.gradient.borrow_mut() = Some(gradient);
+    }
+
+    /// Fills the following elements with their solid color again.
+    pub fn clear(&self) {
+        *self.gradient.borrow_mut() = None;
+    }
+
+    /// The gradient the following elements are filled with, `None` for their solid color.
+    pub(crate) fn get(&self) -> Option<Gradient> {
+        self.gradient.borrow().clone()
+    }
+
+    /// Returns the fill brush for an element with `color` and the bounding box `bounds` in
+    /// physical pixels.
+    pub(crate) fn brush(&self, color: Color, bounds: (f64, f64, f64, f64)) -> utils::Brush {
+        match &*self.gradient.borrow() {
+            Some(gradient) => gradient.to_brush(bounds),
+            None => utils::Brush::SolidColor(color),
+        }
+    }
+}
@@ -1,3 +1,29 @@
 mod backend;
+mod clip;
+mod error;
+mod fonts;
+mod gradient;
+mod measure;
+mod offscreen;
+mod pixels;
+mod recording;
+mod stroke;
+mod tee;
 
 pub use backend::OrbtkBackend;
+pub use clip::{ClipGuard, ClipStack};
+pub use error::OrbtkError;
+#[cfg(feature = "bundled-font")]
+pub use fonts::DEFAULT_FONT;
+pub use fonts::{
+    font_registrations, register_fallback_font, register_font, FontMetrics, FontRegistry,
+};
+pub use gradient::{Gradient, GradientFill};
+pub use measure::{clear_text_cache, set_text_cache_capacity, text_cache_stats, TextCacheStats};
+pub use offscreen::OffscreenCanvas;
+pub use recording::{
+    DisplayList, DrawCommand, RecordedColor, RecordedGradient, RecordedStyle, RecordedTextStyle,
+    RecordingBackend,
+};
+pub use stroke::{LineCap, LineDash, LineJoin};
+pub use tee::{TeeBackend, TeeError};
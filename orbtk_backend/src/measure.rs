@@ -0,0 +1,263 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use orbtk::prelude::*;
+use plotters_backend::BackendTextStyle;
+
+use crate::fonts::FontRegistry;
+use crate::OrbtkError;
+
+/// Number of measurements kept by default.
+const DEFAULT_CAPACITY: usize = 1024;
+
+static TEXT_CACHE: Mutex<Option<TextCache>> = Mutex::new(None);
+
+/// Hit and miss counters of the text measurement cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Number of measurements currently cached.
+    pub len: usize,
+}
+
+type CacheKey = Arc<(String, String, u64)>;
+
+/// The runs of a measured line: where each ends in bytes, the index of its font in the font's
+/// [glyph chain](FontRegistry::glyph_chain) and its width.
+type CachedLine = Vec<(usize, usize, f64)>;
+
+/// A least recently used cache of measured lines, keyed on the text, the OrbTk fonts it is split
+/// into runs with and the font size. Plotters' font family and style are resolved to the font
+/// before, so equal requests from backends with the same fonts share entries. Fonts are
+/// identified by their data as well as their name, backends that register different data under
+/// the same name don't share entries.
+struct TextCache {
+    /// The measurements and the tick they were used last.
+    entries: HashMap<CacheKey, (CachedLine, u64)>,
+    /// Keys in the order they were used, oldest first. A key used again is pushed again, its
+    /// older occurrences are stale and skipped when evicting.
+    recency: VecDeque<(CacheKey, u64)>,
+    capacity: usize,
+    /// Incremented on every use of an entry.
+    clock: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl Default for TextCache {
+    fn default() -> Self {
+        TextCache {
+            entries: HashMap::new(),
+            recency: VecDeque::new(),
+            capacity: DEFAULT_CAPACITY,
+            clock: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+}
+
+impl TextCache {
+    fn get(&mut self, key: &(String, String, u64)) -> Option<CachedLine> {
+        let (key, line) = match self.entries.get_key_value(key) {
+            Some((key, (line, _))) => (key.clone(), line.clone()),
+            None => {
+                self.misses += 1;
+                return None;
+            }
+        };
+        self.hits += 1;
+        self.touch(key);
+        Some(line)
+    }
+
+    fn insert(&mut self, key: (String, String, u64), line: CachedLine) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.shrink_to(self.capacity - 1);
+        let key = Arc::new(key);
+        self.entries.insert(key.clone(), (line, 0));
+        self.touch(key);
+    }
+
+    /// Marks `key` as the most recently used entry.
+    fn touch(&mut self, key: CacheKey) {
+        self.clock += 1;
+        if let Some((_, last_used)) = self.entries.get_mut(&key) {
+            *last_used = self.clock;
+        }
+        self.recency.push_back((key, self.clock));
+        // Drop the stale occurrences once they outnumber the entries, which keeps the queue
+        // bounded while costing O(1) per use on average.
+        if self.recency.len() > 2 * self.entries.len() + 16 {
+            let entries = &self.entries;
+            self.recency
+                .retain(|(key, used)| entries.get(key).map(|(_, last)| last) == Some(used));
+        }
+    }
+
+    /// Drops the least recently used entries until at most `len` are left.
+    fn shrink_to(&mut self, len: usize) {
+        while self.entries.len() > len {
+            let (key, used) = match self.recency.pop_front() {
+                Some(oldest) => oldest,
+                None => break,
+            };
+            if self.entries.get(&key).map(|(_, last_used)| *last_used) == Some(used) {
+                self.entries.remove(&key);
+            }
+        }
+    }
+}
+
+fn with_cache<T>(f: impl FnOnce(&mut TextCache) -> T) -> T {
+    let mut cache = TEXT_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    f(cache.get_or_insert_with(TextCache::default))
+}
+
+/// A line of text, split into runs that are drawn with the same font.
+pub(crate) struct TextLine<'t, 'f> {
+    /// The runs with the font they are drawn with and their width.
+    pub runs: Vec<(&'t str, &'f str, f64)>,
+    pub width: f64,
+}
+
+/// A block of text as it is drawn, in the pixels of the context it was measured with.
+pub(crate) struct TextLayout<'f, 't> {
+    /// The OrbTk font the text is drawn with, runs may use glyph fallbacks.
+    pub font: &'f str,
+    pub size: f64,
+    pub lines: Vec<TextLine<'t, 'f>>,
+    /// Size of the whole block of lines.
+    pub width: f64,
+    pub height: f64,
+    /// Distance from the top of a line to the top of the next.
+    pub line_advance: f64,
+}
+
+/// Lays out `text` in the font `style` resolves to, at `scale` pixels per plotters pixel.
+///
+/// Drawing and every `estimate_text_size` measure through here, so plotters reserves exactly
+/// the space text ends up in. Text is split into lines at `\n`.
+pub(crate) fn layout_text<'f, 't, TStyle: BackendTextStyle>(
+    render_ctx: &mut RenderContext2D,
+    fonts: &'f FontRegistry,
+    text: &'t str,
+    style: &TStyle,
+    scale: f64,
+) -> Result<TextLayout<'f, 't>, OrbtkError> {
+    // Font sizes come in as `f64` from plotters and end up in the rasterizer unchecked.
+    if !style.size().is_finite() {
+        return Err(OrbtkError::NonFiniteCoordinate {
+            context: "font size",
+            value: style.size(),
+        });
+    }
+    let font = fonts.resolve_checked(style.family(), style.style())?;
+    let size = style.size() * scale;
+    let lines = measure_lines(render_ctx, fonts, text, size, font);
+    let line = fonts.metrics(font, size);
+    Ok(TextLayout {
+        font,
+        size,
+        width: lines.iter().map(|line| line.width).fold(0.0, f64::max),
+        height: line.block_height(lines.len()),
+        line_advance: line.height() + line.line_gap,
+        lines,
+    })
+}
+
+/// Splits `text` into lines at `\n` and measures each like [`measure_line`].
+fn measure_lines<'t, 'f>(
+    render_ctx: &mut RenderContext2D,
+    fonts: &'f FontRegistry,
+    text: &'t str,
+    size: f64,
+    font: &'f str,
+) -> Vec<TextLine<'t, 'f>> {
+    text.split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .map(|line| measure_line(render_ctx, fonts, line, size, font))
+        .collect()
+}
+
+/// Splits `line` into runs per [`FontRegistry::runs`] and measures them at `size`.
+///
+/// Lines are cached with their runs, as plotters asks for the size of every tick label and
+/// legend entry each time a chart is drawn.
+fn measure_line<'t, 'f>(
+    render_ctx: &mut RenderContext2D,
+    fonts: &'f FontRegistry,
+    line: &'t str,
+    size: f64,
+    font: &'f str,
+) -> TextLine<'t, 'f> {
+    let chain = fonts.glyph_chain(font);
+    let key = (
+        line.to_string(),
+        fonts.chain_identity(&chain),
+        size.to_bits(),
+    );
+    let cached = match with_cache(|cache| cache.get(&key)) {
+        Some(cached) => cached,
+        None => {
+            let mut end = 0;
+            let measured: CachedLine = fonts
+                .runs(line, font)
+                .into_iter()
+                .map(|(run, run_font)| {
+                    end += run.len();
+                    let index = chain.iter().position(|f| *f == run_font).unwrap_or(0);
+                    (end, index, render_ctx.measure(run, size, run_font).width)
+                })
+                .collect();
+            with_cache(|cache| cache.insert(key, measured.clone()));
+            measured
+        }
+    };
+
+    let mut start = 0;
+    let runs: Vec<_> = cached
+        .into_iter()
+        .map(|(end, index, width)| {
+            let run = &line[start..end];
+            start = end;
+            (run, chain[index], width)
+        })
+        .collect();
+    TextLine {
+        width: runs.iter().map(|(_, _, width)| width).sum(),
+        runs,
+    }
+}
+
+/// Returns the hit and miss counters of the text measurement cache.
+pub fn text_cache_stats() -> TextCacheStats {
+    with_cache(|cache| TextCacheStats {
+        hits: cache.hits,
+        misses: cache.misses,
+        len: cache.entries.len(),
+    })
+}
+
+/// Sets how many measurements are kept, dropping the least recently used ones if needed. A
+/// capacity of 0 disables the cache.
+pub fn set_text_cache_capacity(capacity: usize) {
+    with_cache(|cache| {
+        cache.capacity = capacity;
+        cache.shrink_to(capacity);
+    })
+}
+
+/// Drops all cached measurements and resets the counters.
+pub fn clear_text_cache() {
+    with_cache(|cache| {
+        let capacity = cache.capacity;
+        *cache = TextCache {
+            capacity,
+            ..TextCache::default()
+        };
+    })
+}
@@ -0,0 +1,95 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use orbtk::prelude::*;
+
+use crate::{OrbtkBackend, OrbtkError};
+
+/// An owned `RenderContext2D` to draw charts into without a window, e.g. in tests or batch jobs.
+///
+/// Charts are drawn through the same rasterizer as in the UI, by handing out `OrbtkBackend`s that
+/// borrow the canvas.
+///
+/// ```ignore
+/// let mut canvas = OffscreenCanvas::new(640, 480)?;
+/// {
+///     let root = canvas.backend()?.into_drawing_area();
+///     root.fill(&WHITE)?;
+/// }
+/// canvas.save_png("chart.png")?;
+/// ```
+pub struct OffscreenCanvas {
+    render_ctx: RenderContext2D,
+    width: u32,
+    height: u32,
+}
+
+impl OffscreenCanvas {
+    /// Creates a transparent canvas of `width` x `height` pixels.
+    pub fn new(width: u32, height: u32) -> Result<Self, OrbtkError> {
+        if width == 0 || height == 0 {
+            return Err(OrbtkError::InvalidTarget {
+                width: width as f64,
+                height: height as f64,
+            });
+        }
+        Ok(Self {
+            render_ctx: RenderContext2D::new(width as f64, height as f64),
+            width,
+            height,
+        })
+    }
+
+    /// Returns a backend drawing onto the whole canvas.
+    pub fn backend(&mut self) -> Result<OrbtkBackend<'_>, OrbtkError> {
+        OrbtkBackend::new(&mut self.render_ctx, self.width as f64, self.height as f64)
+    }
+
+    /// The size of the canvas in pixels.
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// The render context backing the canvas.
+    pub fn render_context(&self) -> &RenderContext2D {
+        &self.render_ctx
+    }
+
+    /// Returns the pixels of the canvas row by row as unpremultiplied RGBA bytes.
+    pub fn to_rgba(&self) -> Vec<u8> {
+        let mut rgba = Vec::with_capacity((self.width * self.height * 4) as usize);
+        for &argb in self.render_ctx.data() {
+            let a = argb >> 24;
+            let unpremultiply = |c: u32| {
+                if a == 0 {
+                    0
+                } else {
+                    (c * 255 / a).min(255) as u8
+                }
+            };
+            rgba.push(unpremultiply((argb >> 16) & 0xff));
+            rgba.push(unpremultiply((argb >> 8) & 0xff));
+            rgba.push(unpremultiply(argb & 0xff));
+            rgba.push(a as u8);
+        }
+        rgba
+    }
+
+    /// Writes the canvas to a PNG file.
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> Result<(), OrbtkError> {
+        let export_error = |reason: String| OrbtkError::Export {
+            path: path.as_ref().display().to_string(),
+            reason,
+        };
+        let file = File::create(path.as_ref()).map_err(|e| export_error(e.to_string()))?;
+
+        let mut encoder = png::Encoder::new(BufWriter::new(file), self.width, self.height);
+        encoder.set_color(png::ColorType::RGBA);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&self.to_rgba()))
+            .map_err(|e| export_error(e.to_string()))
+    }
+}
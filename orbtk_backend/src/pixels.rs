@@ -0,0 +1,90 @@
+use orbtk::prelude::*;
+use plotters_backend::BackendColor;
+
+use crate::OrbtkError;
+
+/// Collects `draw_pixel` writes so they reach the render context as a single image.
+///
+/// Pixels are composited onto a transparent, premultiplied ARGB layer which is then drawn over
+/// the context, giving the same result as drawing every pixel on its own.
+#[derive(Debug, Default)]
+pub(crate) struct PixelBuffer {
+    width: u32,
+    height: u32,
+    data: Vec<u32>,
+    /// Bounding box `(x0, y0, x1, y1)` of the pixels written since the last flush.
+    dirty: Option<(u32, u32, u32, u32)>,
+}
+
+impl PixelBuffer {
+    pub fn new(width: u32, height: u32) -> Self {
+        PixelBuffer {
+            width,
+            height,
+            ..Default::default()
+        }
+    }
+
+    pub fn put(&mut self, (x, y): (i32, i32), color: BackendColor) {
+        if x < 0 || y < 0 || x as u32 >= self.width || y as u32 >= self.height {
+            return;
+        }
+        let (x, y) = (x as u32, y as u32);
+        if self.data.is_empty() {
+            self.data = vec![0; (self.width * self.height) as usize];
+        }
+
+        let alpha = color.alpha.clamp(0.0, 1.0);
+        let premultiply = |c: u8| (c as f64 * alpha).round() as u32;
+        let src_a = (alpha * 255.0).round() as u32;
+        let dst = &mut self.data[(y * self.width + x) as usize];
+        let blend = |src: u32, shift: u32| src + ((*dst >> shift) & 0xff) * (255 - src_a) / 255;
+        *dst = blend(src_a, 24) << 24
+            | blend(premultiply(color.rgb.0), 16) << 16
+            | blend(premultiply(color.rgb.1), 8) << 8
+            | blend(premultiply(color.rgb.2), 0);
+
+        self.dirty = Some(match self.dirty {
+            Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x + 1), y1.max(y + 1)),
+            None => (x, y, x + 1, y + 1),
+        });
+    }
+
+    /// Draws the pixels collected since the last flush onto `render_ctx`.
+    pub fn flush(&mut self, render_ctx: &mut RenderContext2D) -> Result<(), OrbtkError> {
+        let (x0, y0, x1, y1) = match self.dirty.take() {
+            Some(dirty) => dirty,
+            None => return Ok(()),
+        };
+
+        let mut region = Vec::with_capacity(((x1 - x0) * (y1 - y0)) as usize);
+        for y in y0..y1 {
+            let row = (y * self.width) as usize;
+            let line = &mut self.data[row + x0 as usize..row + x1 as usize];
+            region.extend_from_slice(line);
+            line.iter_mut().for_each(|pixel| *pixel = 0);
+        }
+
+        draw_argb(
+            render_ctx,
+            (x0, y0),
+            (x1 - x0, y1 - y0),
+            region,
+            "draw_pixel",
+        )
+    }
+}
+
+/// Draws premultiplied ARGB pixels of size `(w, h)` at `pos`.
+pub(crate) fn draw_argb(
+    render_ctx: &mut RenderContext2D,
+    pos: (u32, u32),
+    (w, h): (u32, u32),
+    data: Vec<u32>,
+    operation: &'static str,
+) -> Result<(), OrbtkError> {
+    let image = Image::from_data(w, h, data)
+        .map_err(|reason| OrbtkError::Unsupported { operation, reason })?;
+    render_ctx.draw_image(&image, pos.0 as f64, pos.1 as f64);
+    Ok(())
+}
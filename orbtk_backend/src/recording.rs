@@ -0,0 +1,630 @@
+use orbtk::prelude::*;
+use plotters_backend::text_anchor::{HPos, Pos, VPos};
+use plotters_backend::{
+    BackendColor, BackendCoord, BackendStyle, BackendTextStyle, DrawingBackend, DrawingErrorKind,
+    FontFamily, FontStyle, FontTransform,
+};
+
+use crate::fonts::{self, FontRegistry};
+use crate::measure;
+use crate::{ClipGuard, ClipStack, Gradient, GradientFill, OrbtkBackend, OrbtkError};
+
+/// A color as recorded in a [`DisplayList`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecordedColor {
+    pub rgb: (u8, u8, u8),
+    pub alpha: f64,
+}
+
+impl From<BackendColor> for RecordedColor {
+    fn from(color: BackendColor) -> Self {
+        RecordedColor {
+            rgb: color.rgb,
+            alpha: color.alpha,
+        }
+    }
+}
+
+impl From<RecordedColor> for BackendColor {
+    fn from(color: RecordedColor) -> Self {
+        BackendColor {
+            rgb: color.rgb,
+            alpha: color.alpha,
+        }
+    }
+}
+
+/// The shape style of a recorded primitive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecordedStyle {
+    pub color: RecordedColor,
+    pub stroke_width: u32,
+}
+
+impl RecordedStyle {
+    fn new<S: BackendStyle>(style: &S) -> Self {
+        RecordedStyle {
+            color: style.color().into(),
+            stroke_width: style.stroke_width(),
+        }
+    }
+}
+
+impl BackendStyle for RecordedStyle {
+    fn color(&self) -> BackendColor {
+        self.color.into()
+    }
+
+    fn stroke_width(&self) -> u32 {
+        self.stroke_width
+    }
+}
+
+/// The text style of recorded text.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecordedTextStyle {
+    /// The plotters font family, as returned by `FontFamily::as_str`.
+    pub family: String,
+    /// The plotters font style, as returned by `FontStyle::as_str`.
+    pub style: String,
+    pub size: f64,
+    pub color: RecordedColor,
+    /// Clockwise rotation in degrees, one of 0, 90, 180 and 270.
+    pub rotation: u16,
+    /// Horizontal anchor, -1 for left, 0 for center and 1 for right.
+    pub h_pos: i8,
+    /// Vertical anchor, -1 for top, 0 for center and 1 for bottom.
+    pub v_pos: i8,
+}
+
+impl RecordedTextStyle {
+    fn new<S: BackendTextStyle>(style: &S) -> Self {
+        let anchor = style.anchor();
+        RecordedTextStyle {
+            family: style.family().as_str().to_string(),
+            style: style.style().as_str().to_string(),
+            size: style.size(),
+            color: style.color().into(),
+            rotation: match style.transform() {
+                FontTransform::None => 0,
+                FontTransform::Rotate90 => 90,
+                FontTransform::Rotate180 => 180,
+                FontTransform::Rotate270 => 270,
+            },
+            h_pos: match anchor.h_pos {
+                HPos::Left => -1,
+                HPos::Center => 0,
+                HPos::Right => 1,
+            },
+            v_pos: match anchor.v_pos {
+                VPos::Top => -1,
+                VPos::Center => 0,
+                VPos::Bottom => 1,
+            },
+        }
+    }
+}
+
+impl BackendTextStyle for RecordedTextStyle {
+    type FontError = OrbtkError;
+
+    fn color(&self) -> BackendColor {
+        self.color.into()
+    }
+
+    fn size(&self) -> f64 {
+        self.size
+    }
+
+    fn transform(&self) -> FontTransform {
+        match self.rotation {
+            90 => FontTransform::Rotate90,
+            180 => FontTransform::Rotate180,
+            270 => FontTransform::Rotate270,
+            _ => FontTransform::None,
+        }
+    }
+
+    fn style(&self) -> FontStyle {
+        FontStyle::from(self.style.as_str())
+    }
+
+    fn anchor(&self) -> Pos {
+        let h_pos = match self.h_pos {
+            -1 => HPos::Left,
+            1 => HPos::Right,
+            _ => HPos::Center,
+        };
+        let v_pos = match self.v_pos {
+            -1 => VPos::Top,
+            1 => VPos::Bottom,
+            _ => VPos::Center,
+        };
+        Pos::new(h_pos, v_pos)
+    }
+
+    fn family(&self) -> FontFamily {
+        FontFamily::from(self.family.as_str())
+    }
+
+    fn layout_box(&self, _text: &str) -> Result<((i32, i32), (i32, i32)), Self::FontError> {
+        Err(OrbtkError::Unsupported {
+            operation: "layout_box",
+            reason: "recorded text is laid out by the backend it is replayed onto".to_string(),
+        })
+    }
+
+    fn draw<E, DrawFunc: FnMut(i32, i32, BackendColor) -> Result<(), E>>(
+        &self,
+        _text: &str,
+        _pos: BackendCoord,
+        _draw: DrawFunc,
+    ) -> Result<Result<(), E>, Self::FontError> {
+        Err(OrbtkError::Unsupported {
+            operation: "draw",
+            reason: "recorded text is rasterized by the backend it is replayed onto".to_string(),
+        })
+    }
+}
+
+/// A fill gradient as recorded in a [`DisplayList`], see [`Gradient`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RecordedGradient {
+    Linear {
+        start: (f64, f64),
+        end: (f64, f64),
+        stops: Vec<(f64, RecordedColor)>,
+    },
+    Radial {
+        center: (f64, f64),
+        radius: f64,
+        stops: Vec<(f64, RecordedColor)>,
+    },
+}
+
+fn convert_stops<A: Copy, B: From<A>>(stops: Vec<(f64, A)>) -> Vec<(f64, B)> {
+    stops
+        .into_iter()
+        .map(|(pos, color)| (pos, color.into()))
+        .collect()
+}
+
+impl From<Gradient> for RecordedGradient {
+    fn from(gradient: Gradient) -> Self {
+        match gradient {
+            Gradient::Linear { start, end, stops } => RecordedGradient::Linear {
+                start,
+                end,
+                stops: convert_stops(stops),
+            },
+            Gradient::Radial {
+                center,
+                radius,
+                stops,
+            } => RecordedGradient::Radial {
+                center,
+                radius,
+                stops: convert_stops(stops),
+            },
+        }
+    }
+}
+
+impl From<RecordedGradient> for Gradient {
+    fn from(gradient: RecordedGradient) -> Self {
+        match gradient {
+            RecordedGradient::Linear { start, end, stops } => Gradient::Linear {
+                start,
+                end,
+                stops: convert_stops(stops),
+            },
+            RecordedGradient::Radial {
+                center,
+                radius,
+                stops,
+            } => Gradient::Radial {
+                center,
+                radius,
+                stops: convert_stops(stops),
+            },
+        }
+    }
+}
+
+/// A primitive recorded by [`RecordingBackend`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DrawCommand {
+    Pixel {
+        point: BackendCoord,
+        color: RecordedColor,
+    },
+    Line {
+        from: BackendCoord,
+        to: BackendCoord,
+        style: RecordedStyle,
+    },
+    Rect {
+        upper_left: BackendCoord,
+        bottom_right: BackendCoord,
+        style: RecordedStyle,
+        fill: bool,
+    },
+    Path {
+        points: Vec<BackendCoord>,
+        style: RecordedStyle,
+    },
+    Circle {
+        center: BackendCoord,
+        radius: u32,
+        style: RecordedStyle,
+        fill: bool,
+    },
+    Polygon {
+        points: Vec<BackendCoord>,
+        style: RecordedStyle,
+    },
+    Text {
+        text: String,
+        style: RecordedTextStyle,
+        pos: BackendCoord,
+    },
+    Bitmap {
+        pos: BackendCoord,
+        size: (u32, u32),
+        data: Vec<u8>,
+    },
+    /// The following primitives are clipped to the rectangle spanned by the two corners, or not
+    /// at all if `None`, see [`ClipStack`].
+    Clip {
+        rect: Option<(BackendCoord, BackendCoord)>,
+    },
+    /// The following filled primitives are filled with the gradient, or with their solid color
+    /// if `None`, see [`GradientFill`].
+    Fill { gradient: Option<RecordedGradient> },
+}
+
+/// The primitives of a chart, recorded once and replayed as often as needed.
+///
+/// Replaying draws the chart without re-evaluating the plotters code that built it, so static
+/// charts can be repainted cheaply.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DisplayList {
+    size: (u32, u32),
+    commands: Vec<DrawCommand>,
+}
+
+impl DisplayList {
+    /// Creates an empty display list for a chart of `width` x `height` pixels.
+    pub fn new(width: u32, height: u32) -> Self {
+        DisplayList {
+            size: (width, height),
+            commands: vec![],
+        }
+    }
+
+    /// The size of the recorded chart.
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    /// The recorded primitives in drawing order.
+    pub fn commands(&self) -> &[DrawCommand] {
+        &self.commands
+    }
+
+    /// Removes all recorded primitives.
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+
+    /// Draws the recorded primitives onto a plotters backend.
+    ///
+    /// Recorded text is passed to the backend's `draw_text`, so it can only be replayed onto
+    /// backends that lay out text themselves, like `OrbtkBackend` and plotters' `SVGBackend`.
+    /// Backends that rasterize text through the style, like `BitMapBackend`, fail with a
+    /// `FontError` on the first text. Lists without text replay onto any backend.
+    ///
+    /// Plotters backends have no clip or gradients, so [`Clip`](DrawCommand::Clip) and
+    /// [`Fill`](DrawCommand::Fill) commands are skipped and everything is drawn unclipped in
+    /// solid colors. Use [`replay_onto_backend`](DisplayList::replay_onto_backend) to keep them.
+    pub fn replay<DB: DrawingBackend>(
+        &self,
+        backend: &mut DB,
+    ) -> Result<(), DrawingErrorKind<DB::ErrorType>> {
+        self.replay_with(backend, |_| {})
+    }
+
+    /// Like [`replay`](DisplayList::replay), but applies the recorded clip and fill gradients
+    /// through the backend's [`clip_stack`](OrbtkBackend::clip_stack) and
+    /// [`gradient_fill`](OrbtkBackend::gradient_fill). Recorded clips are intersected with the
+    /// clip already on the stack, the backend's clip and gradient are left as they were.
+    pub fn replay_onto_backend(
+        &self,
+        backend: &mut OrbtkBackend,
+    ) -> Result<(), DrawingErrorKind<OrbtkError>> {
+        let (clip, fill) = (backend.clip_stack(), backend.gradient_fill());
+        let previous_fill = fill.get();
+        let mut clip_guard: Option<ClipGuard> = None;
+        let result = self.replay_with(backend, |command| match command {
+            DrawCommand::Clip { rect } => {
+                // Pop the previous rectangle before pushing, its guard truncates the stack.
+                drop(clip_guard.take());
+                clip_guard =
+                    rect.map(|(upper_left, bottom_right)| clip.push(upper_left, bottom_right));
+            }
+            DrawCommand::Fill { gradient } => match gradient {
+                Some(gradient) => fill.set(gradient.clone().into()),
+                None => fill.clear(),
+            },
+            _ => {}
+        });
+        drop(clip_guard);
+        match previous_fill {
+            Some(gradient) => fill.set(gradient),
+            None => fill.clear(),
+        }
+        result
+    }
+
+    /// Replays the primitives and passes the clip and fill commands to `apply`.
+    fn replay_with<DB: DrawingBackend>(
+        &self,
+        backend: &mut DB,
+        mut apply: impl FnMut(&DrawCommand),
+    ) -> Result<(), DrawingErrorKind<DB::ErrorType>> {
+        for command in &self.commands {
+            match command {
+                DrawCommand::Pixel { point, color } => {
+                    backend.draw_pixel(*point, (*color).into())?
+                }
+                DrawCommand::Line { from, to, style } => backend.draw_line(*from, *to, style)?,
+                DrawCommand::Rect {
+                    upper_left,
+                    bottom_right,
+                    style,
+                    fill,
+                } => backend.draw_rect(*upper_left, *bottom_right, style, *fill)?,
+                DrawCommand::Path { points, style } => {
+                    backend.draw_path(points.iter().copied(), style)?
+                }
+                DrawCommand::Circle {
+                    center,
+                    radius,
+                    style,
+                    fill,
+                } => backend.draw_circle(*center, *radius, style, *fill)?,
+                DrawCommand::Polygon { points, style } => {
+                    backend.fill_polygon(points.iter().copied(), style)?
+                }
+                DrawCommand::Text { text, style, pos } => backend.draw_text(text, style, *pos)?,
+                DrawCommand::Bitmap { pos, size, data } => {
+                    backend.blit_bitmap(*pos, *size, data)?
+                }
+                DrawCommand::Clip { .. } | DrawCommand::Fill { .. } => apply(command),
+            }
+        }
+        backend.present()
+    }
+
+    /// Draws the recorded primitives onto a render context, through an `OrbtkBackend` of the
+    /// recorded size.
+    pub fn replay_onto(&self, render_ctx: &mut RenderContext2D) -> Result<(), OrbtkError> {
+        let mut backend = OrbtkBackend::new(render_ctx, self.size.0 as f64, self.size.1 as f64)?;
+        self.replay_onto_backend(&mut backend).map_err(|e| match e {
+            DrawingErrorKind::DrawingError(e) => e,
+            DrawingErrorKind::FontError(e) => OrbtkError::Unsupported {
+                operation: "replay",
+                reason: e.to_string(),
+            },
+        })
+    }
+}
+
+/// A plotters backend that records primitives into a [`DisplayList`] instead of drawing them.
+///
+/// Text is measured with the same fonts `OrbtkBackend` uses, so the recorded layout matches a
+/// chart drawn directly. Changes of the clip and the fill gradient, set through the handles
+/// returned by [`clip_stack`](RecordingBackend::clip_stack) and
+/// [`gradient_fill`](RecordingBackend::gradient_fill), are recorded before the next primitive.
+pub struct RecordingBackend<'a> {
+    list: &'a mut DisplayList,
+    measure_ctx: RefCell<RenderContext2D>,
+    fonts: FontRegistry,
+    clip: ClipStack,
+    gradient: GradientFill,
+    /// The clip and gradient of the last recorded primitive.
+    recorded_clip: Option<(BackendCoord, BackendCoord)>,
+    recorded_fill: Option<RecordedGradient>,
+}
+
+impl<'a> RecordingBackend<'a> {
+    /// Creates a backend appending to `list`, with the size of the list.
+    pub fn new(list: &'a mut DisplayList) -> Result<Self, OrbtkError> {
+        let mut measure_ctx = RenderContext2D::new(1.0, 1.0);
+        let fonts = fonts::shared_fonts(&mut measure_ctx)?;
+        Ok(RecordingBackend {
+            list,
+            measure_ctx: RefCell::new(measure_ctx),
+            fonts,
+            clip: ClipStack::default(),
+            gradient: GradientFill::default(),
+            recorded_clip: None,
+            recorded_fill: None,
+        })
+    }
+
+    /// Registers a font used to measure text, see `OrbtkBackend::register_font`. The backend the
+    /// list is replayed onto needs the same fonts.
+    pub fn register_font(
+        &mut self,
+        family: FontFamily,
+        style: FontStyle,
+        name: &str,
+        bytes: &'static [u8],
+    ) -> Result<(), OrbtkError> {
+        self.fonts
+            .register(self.measure_ctx.get_mut(), family, style, name, bytes)
+    }
+
+    /// Returns a handle to the clip stack, see `OrbtkBackend::clip_stack`.
+    pub fn clip_stack(&self) -> ClipStack {
+        self.clip.clone()
+    }
+
+    /// Returns a handle to set the fill gradient, see `OrbtkBackend::gradient_fill`.
+    pub fn gradient_fill(&self) -> GradientFill {
+        self.gradient.clone()
+    }
+
+    fn record(&mut self, command: DrawCommand) -> Result<(), DrawingErrorKind<OrbtkError>> {
+        let clip = self.clip.effective();
+        if clip != self.recorded_clip {
+            self.list.commands.push(DrawCommand::Clip { rect: clip });
+            self.recorded_clip = clip;
+        }
+        let fill = self.gradient.get().map(RecordedGradient::from);
+        if fill != self.recorded_fill {
+            self.list.commands.push(DrawCommand::Fill {
+                gradient: fill.clone(),
+            });
+            self.recorded_fill = fill;
+        }
+        self.list.commands.push(command);
+        Ok(())
+    }
+}
+
+impl<'a> DrawingBackend for RecordingBackend<'a> {
+    type ErrorType = OrbtkError;
+
+    fn get_size(&self) -> (u32, u32) {
+        self.list.size
+    }
+
+    fn ensure_prepared(&mut self) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        Ok(())
+    }
+
+    fn present(&mut self) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        Ok(())
+    }
+
+    fn draw_pixel(
+        &mut self,
+        point: BackendCoord,
+        color: BackendColor,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.record(DrawCommand::Pixel {
+            point,
+            color: color.into(),
+        })
+    }
+
+    fn draw_line<S: BackendStyle>(
+        &mut self,
+        from: BackendCoord,
+        to: BackendCoord,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.record(DrawCommand::Line {
+            from,
+            to,
+            style: RecordedStyle::new(style),
+        })
+    }
+
+    fn draw_rect<S: BackendStyle>(
+        &mut self,
+        upper_left: BackendCoord,
+        bottom_right: BackendCoord,
+        style: &S,
+        fill: bool,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.record(DrawCommand::Rect {
+            upper_left,
+            bottom_right,
+            style: RecordedStyle::new(style),
+            fill,
+        })
+    }
+
+    fn draw_path<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        path: I,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.record(DrawCommand::Path {
+            points: path.into_iter().collect(),
+            style: RecordedStyle::new(style),
+        })
+    }
+
+    fn draw_circle<S: BackendStyle>(
+        &mut self,
+        center: BackendCoord,
+        radius: u32,
+        style: &S,
+        fill: bool,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.record(DrawCommand::Circle {
+            center,
+            radius,
+            style: RecordedStyle::new(style),
+            fill,
+        })
+    }
+
+    fn fill_polygon<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        vert: I,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.record(DrawCommand::Polygon {
+            points: vert.into_iter().collect(),
+            style: RecordedStyle::new(style),
+        })
+    }
+
+    fn draw_text<TStyle: BackendTextStyle>(
+        &mut self,
+        text: &str,
+        style: &TStyle,
+        pos: BackendCoord,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.record(DrawCommand::Text {
+            text: text.to_string(),
+            style: RecordedTextStyle::new(style),
+            pos,
+        })
+    }
+
+    fn blit_bitmap<'b>(
+        &mut self,
+        pos: BackendCoord,
+        (iw, ih): (u32, u32),
+        src: &'b [u8],
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.record(DrawCommand::Bitmap {
+            pos,
+            size: (iw, ih),
+            data: src.to_vec(),
+        })
+    }
+
+    fn estimate_text_size<TStyle: BackendTextStyle>(
+        &self,
+        text: &str,
+        style: &TStyle,
+    ) -> Result<(u32, u32), DrawingErrorKind<Self::ErrorType>> {
+        let mut measure_ctx = self.measure_ctx.borrow_mut();
+        let layout = measure::layout_text(&mut measure_ctx, &self.fonts, text, style, 1.0)
+            .map_err(DrawingErrorKind::DrawingError)?;
+        Ok((layout.width.ceil() as u32, layout.height.ceil() as u32))
+    }
+}
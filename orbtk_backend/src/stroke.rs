@@ -0,0 +1,317 @@
+use orbtk::prelude::*;
+
+/// A dash pattern for stroked lines, following the semantics of the canvas `setLineDash`.
+///
+/// `pattern` alternates between the lengths of drawn and skipped parts, an odd number of entries
+/// is repeated to make it even. `offset` shifts the start of the pattern along the line. The
+/// default pattern draws solid lines.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LineDash {
+    pattern: Vec<f64>,
+    offset: f64,
+}
+
+impl LineDash {
+    /// Creates a dash pattern. Patterns with negative or non-finite entries, or that have no
+    /// length at all, draw solid lines.
+    pub fn new(pattern: &[f64], offset: f64) -> Self {
+        let valid = pattern.iter().all(|len| len.is_finite() && *len >= 0.0)
+            && pattern.iter().sum::<f64>() > 0.0
+            && offset.is_finite();
+        if !valid {
+            return LineDash::default();
+        }
+
+        let pattern = if pattern.len() % 2 == 1 {
+            pattern.repeat(2)
+        } else {
+            pattern.to_vec()
+        };
+        LineDash { pattern, offset }
+    }
+
+    /// Returns `true` if lines are drawn without gaps.
+    pub fn is_solid(&self) -> bool {
+        self.pattern.is_empty()
+    }
+
+    /// Splits a polyline into the parts that are drawn. The pattern continues across the
+    /// vertices of the polyline instead of restarting at each segment.
+    pub fn apply(&self, points: &[(f64, f64)]) -> Vec<Vec<(f64, f64)>> {
+        if self.is_solid() || points.is_empty() {
+            return vec![points.to_vec()];
+        }
+
+        let total: f64 = self.pattern.iter().sum();
+        let mut phase = self.offset.rem_euclid(total);
+        let mut index = 0;
+        while phase >= self.pattern[index] {
+            phase -= self.pattern[index];
+            index = (index + 1) % self.pattern.len();
+        }
+        let mut remaining = self.pattern[index] - phase;
+
+        let mut dashes = vec![];
+        let mut current = if index % 2 == 0 {
+            vec![points[0]]
+        } else {
+            vec![]
+        };
+        for segment in points.windows(2) {
+            let (mut start, end) = (segment[0], segment[1]);
+            let mut len = ((end.0 - start.0).powi(2) + (end.1 - start.1).powi(2)).sqrt();
+            while len > remaining {
+                let t = remaining / len;
+                let split = (
+                    start.0 + (end.0 - start.0) * t,
+                    start.1 + (end.1 - start.1) * t,
+                );
+                if index % 2 == 0 {
+                    current.push(split);
+                    dashes.push(std::mem::take(&mut current));
+                } else {
+                    current = vec![split];
+                }
+                start = split;
+                len -= remaining;
+                index = (index + 1) % self.pattern.len();
+                remaining = self.pattern[index];
+            }
+            remaining -= len;
+            if index % 2 == 0 {
+                current.push(end);
+            }
+        }
+        if current.len() > 1 {
+            dashes.push(current);
+        }
+        dashes
+    }
+}
+
+/// Adds a polyline to the current path of `render_ctx`.
+pub(crate) fn trace(render_ctx: &mut RenderContext2D, points: &[(f64, f64)]) {
+    for (index, point) in points.iter().enumerate() {
+        if index == 0 {
+            render_ctx.move_to(point.0, point.1);
+        } else {
+            render_ctx.line_to(point.0, point.1);
+        }
+    }
+}
+
+/// Approximates a circle with a closed polyline, used where the outline has to be walked.
+pub(crate) fn circle_points(center: (f64, f64), radius: f64) -> Vec<(f64, f64)> {
+    let steps = ((radius * std::f64::consts::PI).ceil() as usize).max(16);
+    (0..=steps)
+        .map(|step| {
+            let angle = std::f64::consts::PI * 2.0 * step as f64 / steps as f64;
+            (
+                center.0 + radius * angle.cos(),
+                center.1 + radius * angle.sin(),
+            )
+        })
+        .collect()
+}
+
+/// The shape used at the ends of stroked lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineCap {
+    /// The line ends exactly at its end points.
+    Butt,
+    /// The line ends with a half circle.
+    Round,
+    /// The line is extended by half its width.
+    Square,
+}
+
+/// The shape used where two segments of a stroked path meet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineJoin {
+    /// The outer edges are extended until they meet, limited by the miter limit.
+    Miter,
+    /// The corner is rounded off.
+    Round,
+    /// The corner is cut off.
+    Bevel,
+}
+
+/// Cap, join and miter limit used by `draw_line` and `draw_path`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct LineStyle {
+    pub cap: LineCap,
+    pub join: LineJoin,
+    pub miter_limit: f64,
+}
+
+impl Default for LineStyle {
+    /// What `RenderContext2D::stroke` draws.
+    fn default() -> Self {
+        LineStyle {
+            cap: LineCap::Butt,
+            join: LineJoin::Miter,
+            miter_limit: 10.0,
+        }
+    }
+}
+
+impl LineStyle {
+    /// `RenderContext2D` can't be configured with caps and joins, so anything but its default
+    /// stroke is built as a filled outline.
+    pub fn is_native(&self) -> bool {
+        *self == LineStyle::default()
+    }
+}
+
+/// Adds the outline of a polyline stroked with `width` to the current path of `render_ctx`, to be
+/// drawn with `fill`.
+///
+/// The outline is made of overlapping polygons that all share the same orientation, so the
+/// non-zero fill rule paints every pixel once and translucent lines stay even.
+pub(crate) fn trace_outline(
+    render_ctx: &mut RenderContext2D,
+    points: &[(f64, f64)],
+    width: f64,
+    style: LineStyle,
+) {
+    let half = width / 2.0;
+    let mut points: Vec<_> = points.to_vec();
+    points.dedup();
+    let closed = points.len() > 2 && points.first() == points.last();
+
+    if points.is_empty() {
+        return;
+    }
+    if points.len() == 1 {
+        let p = points[0];
+        match style.cap {
+            LineCap::Butt => {}
+            LineCap::Round => add_polygon(render_ctx, circle_points(p, half)),
+            LineCap::Square => add_polygon(
+                render_ctx,
+                vec![
+                    (p.0 - half, p.1 - half),
+                    (p.0 + half, p.1 - half),
+                    (p.0 + half, p.1 + half),
+                    (p.0 - half, p.1 + half),
+                ],
+            ),
+        }
+        return;
+    }
+
+    let last = points.len() - 1;
+    if !closed {
+        match style.cap {
+            LineCap::Butt => {}
+            LineCap::Round => {
+                add_polygon(render_ctx, circle_points(points[0], half));
+                add_polygon(render_ctx, circle_points(points[last], half));
+            }
+            LineCap::Square => {
+                let d = direction(points[0], points[1]);
+                points[0] = (points[0].0 - d.0 * half, points[0].1 - d.1 * half);
+                let d = direction(points[last - 1], points[last]);
+                points[last] = (points[last].0 + d.0 * half, points[last].1 + d.1 * half);
+            }
+        }
+    }
+
+    for segment in points.windows(2) {
+        let (a, b) = (segment[0], segment[1]);
+        let d = direction(a, b);
+        let n = (-d.1 * half, d.0 * half);
+        add_polygon(
+            render_ctx,
+            vec![
+                (a.0 + n.0, a.1 + n.1),
+                (b.0 + n.0, b.1 + n.1),
+                (b.0 - n.0, b.1 - n.1),
+                (a.0 - n.0, a.1 - n.1),
+            ],
+        );
+    }
+
+    let mut joins: Vec<_> = points.windows(3).map(|w| (w[0], w[1], w[2])).collect();
+    if closed {
+        joins.push((points[last - 1], points[0], points[1]));
+    }
+    for (prev, vertex, next) in joins {
+        trace_join(render_ctx, prev, vertex, next, half, style);
+    }
+}
+
+fn trace_join(
+    render_ctx: &mut RenderContext2D,
+    prev: (f64, f64),
+    vertex: (f64, f64),
+    next: (f64, f64),
+    half: f64,
+    style: LineStyle,
+) {
+    let d1 = direction(prev, vertex);
+    let d2 = direction(vertex, next);
+    let cross = d1.0 * d2.1 - d1.1 * d2.0;
+    if cross.abs() < 1e-9 && d1.0 * d2.0 + d1.1 * d2.1 > 0.0 {
+        return;
+    }
+
+    // The offsets on the outer side of the turn, relative to the vertex.
+    let side = if cross > 0.0 { -half } else { half };
+    let o1 = (-d1.1 * side, d1.0 * side);
+    let o2 = (-d2.1 * side, d2.0 * side);
+    let p1 = (vertex.0 + o1.0, vertex.1 + o1.1);
+    let p2 = (vertex.0 + o2.0, vertex.1 + o2.1);
+
+    match style.join {
+        LineJoin::Round => add_polygon(render_ctx, circle_points(vertex, half)),
+        LineJoin::Bevel => add_polygon(render_ctx, vec![vertex, p1, p2]),
+        LineJoin::Miter => {
+            let m = (o1.0 + o2.0, o1.1 + o2.1);
+            let m_len = (m.0 * m.0 + m.1 * m.1).sqrt();
+            // Ratio between the miter length and the line width, 1 / cos of half the turn.
+            let cos = (m.0 * o1.0 + m.1 * o1.1) / (m_len * half);
+            if m_len < 1e-9 || 1.0 / cos > style.miter_limit {
+                add_polygon(render_ctx, vec![vertex, p1, p2]);
+            } else {
+                let scale = half / cos / m_len;
+                let tip = (vertex.0 + m.0 * scale, vertex.1 + m.1 * scale);
+                add_polygon(render_ctx, vec![vertex, p1, tip, p2]);
+            }
+        }
+    }
+}
+
+fn direction(from: (f64, f64), to: (f64, f64)) -> (f64, f64) {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let len = (dx * dx + dy * dy).sqrt();
+    if len == 0.0 {
+        (0.0, 0.0)
+    } else {
+        (dx / len, dy / len)
+    }
+}
+
+/// Adds a closed polygon with positive orientation to the current path.
+fn add_polygon(render_ctx: &mut RenderContext2D, mut polygon: Vec<(f64, f64)>) {
+    let area: f64 = polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(a, b)| a.0 * b.1 - b.0 * a.1)
+        .sum();
+    if area.abs() < 1e-9 {
+        return;
+    }
+    if area < 0.0 {
+        polygon.reverse();
+    }
+
+    for (index, point) in polygon.iter().enumerate() {
+        if index == 0 {
+            render_ctx.move_to(point.0, point.1);
+        } else {
+            render_ctx.line_to(point.0, point.1);
+        }
+    }
+    render_ctx.close_path();
+}
@@ -0,0 +1,198 @@
+use plotters_backend::{
+    BackendColor, BackendCoord, BackendStyle, BackendTextStyle, DrawingBackend, DrawingErrorKind,
+};
+
+/// The error of a [`TeeBackend`], telling which of the two backends failed.
+#[derive(Debug)]
+pub enum TeeError<A, B> {
+    First(A),
+    Second(B),
+}
+
+impl<A: std::fmt::Display, B: std::fmt::Display> std::fmt::Display for TeeError<A, B> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TeeError::First(e) => write!(fmt, "first backend: {}", e),
+            TeeError::Second(e) => write!(fmt, "second backend: {}", e),
+        }
+    }
+}
+
+impl<A: std::error::Error, B: std::error::Error> std::error::Error for TeeError<A, B> {}
+
+/// A backend that forwards every primitive to two backends, e.g. an `OrbtkBackend` on screen and
+/// a `BitMapBackend` or `SVGBackend` to export exactly what is shown.
+///
+/// The reported size is the area both backends can draw to. Text is laid out with the
+/// measurements of the first backend.
+pub struct TeeBackend<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A: DrawingBackend, B: DrawingBackend> TeeBackend<A, B> {
+    pub fn new(first: A, second: B) -> Self {
+        TeeBackend { first, second }
+    }
+
+    /// Returns the two backends.
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+}
+
+type TeeResult<A, B> = Result<
+    (),
+    DrawingErrorKind<TeeError<<A as DrawingBackend>::ErrorType, <B as DrawingBackend>::ErrorType>>,
+>;
+
+fn first<A: DrawingBackend, B: DrawingBackend>(
+    e: DrawingErrorKind<A::ErrorType>,
+) -> DrawingErrorKind<TeeError<A::ErrorType, B::ErrorType>> {
+    match e {
+        DrawingErrorKind::DrawingError(e) => DrawingErrorKind::DrawingError(TeeError::First(e)),
+        DrawingErrorKind::FontError(e) => DrawingErrorKind::FontError(e),
+    }
+}
+
+fn second<A: DrawingBackend, B: DrawingBackend>(
+    e: DrawingErrorKind<B::ErrorType>,
+) -> DrawingErrorKind<TeeError<A::ErrorType, B::ErrorType>> {
+    match e {
+        DrawingErrorKind::DrawingError(e) => DrawingErrorKind::DrawingError(TeeError::Second(e)),
+        DrawingErrorKind::FontError(e) => DrawingErrorKind::FontError(e),
+    }
+}
+
+impl<A: DrawingBackend, B: DrawingBackend> DrawingBackend for TeeBackend<A, B> {
+    type ErrorType = TeeError<A::ErrorType, B::ErrorType>;
+
+    fn get_size(&self) -> (u32, u32) {
+        let (w1, h1) = self.first.get_size();
+        let (w2, h2) = self.second.get_size();
+        (w1.min(w2), h1.min(h2))
+    }
+
+    fn ensure_prepared(&mut self) -> TeeResult<A, B> {
+        self.first.ensure_prepared().map_err(first::<A, B>)?;
+        self.second.ensure_prepared().map_err(second::<A, B>)
+    }
+
+    fn present(&mut self) -> TeeResult<A, B> {
+        self.first.present().map_err(first::<A, B>)?;
+        self.second.present().map_err(second::<A, B>)
+    }
+
+    fn draw_pixel(&mut self, point: BackendCoord, color: BackendColor) -> TeeResult<A, B> {
+        self.first.draw_pixel(point, color).map_err(first::<A, B>)?;
+        self.second.draw_pixel(point, color).map_err(second::<A, B>)
+    }
+
+    fn draw_line<S: BackendStyle>(
+        &mut self,
+        from: BackendCoord,
+        to: BackendCoord,
+        style: &S,
+    ) -> TeeResult<A, B> {
+        self.first
+            .draw_line(from, to, style)
+            .map_err(first::<A, B>)?;
+        self.second
+            .draw_line(from, to, style)
+            .map_err(second::<A, B>)
+    }
+
+    fn draw_rect<S: BackendStyle>(
+        &mut self,
+        upper_left: BackendCoord,
+        bottom_right: BackendCoord,
+        style: &S,
+        fill: bool,
+    ) -> TeeResult<A, B> {
+        self.first
+            .draw_rect(upper_left, bottom_right, style, fill)
+            .map_err(first::<A, B>)?;
+        self.second
+            .draw_rect(upper_left, bottom_right, style, fill)
+            .map_err(second::<A, B>)
+    }
+
+    fn draw_path<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        path: I,
+        style: &S,
+    ) -> TeeResult<A, B> {
+        let path: Vec<_> = path.into_iter().collect();
+        self.first
+            .draw_path(path.iter().copied(), style)
+            .map_err(first::<A, B>)?;
+        self.second.draw_path(path, style).map_err(second::<A, B>)
+    }
+
+    fn draw_circle<S: BackendStyle>(
+        &mut self,
+        center: BackendCoord,
+        radius: u32,
+        style: &S,
+        fill: bool,
+    ) -> TeeResult<A, B> {
+        self.first
+            .draw_circle(center, radius, style, fill)
+            .map_err(first::<A, B>)?;
+        self.second
+            .draw_circle(center, radius, style, fill)
+            .map_err(second::<A, B>)
+    }
+
+    fn fill_polygon<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        vert: I,
+        style: &S,
+    ) -> TeeResult<A, B> {
+        let vert: Vec<_> = vert.into_iter().collect();
+        self.first
+            .fill_polygon(vert.iter().copied(), style)
+            .map_err(first::<A, B>)?;
+        self.second
+            .fill_polygon(vert, style)
+            .map_err(second::<A, B>)
+    }
+
+    fn draw_text<TStyle: BackendTextStyle>(
+        &mut self,
+        text: &str,
+        style: &TStyle,
+        pos: BackendCoord,
+    ) -> TeeResult<A, B> {
+        self.first
+            .draw_text(text, style, pos)
+            .map_err(first::<A, B>)?;
+        self.second
+            .draw_text(text, style, pos)
+            .map_err(second::<A, B>)
+    }
+
+    fn estimate_text_size<TStyle: BackendTextStyle>(
+        &self,
+        text: &str,
+        style: &TStyle,
+    ) -> Result<(u32, u32), DrawingErrorKind<Self::ErrorType>> {
+        self.first
+            .estimate_text_size(text, style)
+            .map_err(first::<A, B>)
+    }
+
+    fn blit_bitmap<'a>(
+        &mut self,
+        pos: BackendCoord,
+        (iw, ih): (u32, u32),
+        src: &'a [u8],
+    ) -> TeeResult<A, B> {
+        self.first
+            .blit_bitmap(pos, (iw, ih), src)
+            .map_err(first::<A, B>)?;
+        self.second
+            .blit_bitmap(pos, (iw, ih), src)
+            .map_err(second::<A, B>)
+    }
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use common::{assert_color_eq, bitmap_pixel, orbtk_pixel};
+use orbtk::prelude::RenderContext2D;
+use orbtk_backend::OrbtkBackend;
+use plotters::prelude::*;
+
+const WIDTH: u32 = 200;
+const HEIGHT: u32 = 100;
+
+/// Draws overlapping semi-transparent histograms followed by an opaque rectangle.
+fn draw_histograms<DB: DrawingBackend>(backend: DB) {
+    let root = backend.into_drawing_area();
+    root.fill(&WHITE).unwrap();
+    let mut chart = ChartBuilder::on(&root)
+        .build_cartesian_2d((0u32..4u32).into_segmented(), 0u32..4u32)
+        .unwrap();
+
+    for _ in 0..2 {
+        chart
+            .draw_series(
+                Histogram::vertical(&chart)
+                    .margin(0)
+                    .style(RED.mix(0.5).filled())
+                    .data(vec![(0, 2), (1, 4), (2, 1), (3, 3)]),
+            )
+            .unwrap();
+    }
+    chart
+        .draw_series(
+            Histogram::vertical(&chart)
+                .margin(0)
+                .style(BLUE.mix(0.5).filled())
+                .data(vec![(2, 4)]),
+        )
+        .unwrap();
+
+    // Nothing drawn after the semi-transparent bars may inherit their alpha.
+    root.draw(&Rectangle::new([(0, 0), (20, 10)], GREEN.filled()))
+        .unwrap();
+}
+
+#[test]
+fn semi_transparent_bars_blend_like_bitmap_backend() {
+    let mut render_ctx = RenderContext2D::new(WIDTH as f64, HEIGHT as f64);
+    draw_histograms(OrbtkBackend::new(&mut render_ctx, WIDTH as f64, HEIGHT as f64).unwrap());
+
+    let mut buffer = vec![0u8; (WIDTH * HEIGHT * 3) as usize];
+    draw_histograms(BitMapBackend::with_buffer(&mut buffer, (WIDTH, HEIGHT)));
+
+    let samples = [
+        // inside the first bar, red drawn twice at 50%
+        (WIDTH / 8, HEIGHT * 7 / 8),
+        // above the first bar, background only
+        (WIDTH / 8, HEIGHT / 8),
+        // full height bar
+        (WIDTH * 3 / 8, HEIGHT / 2),
+        // blue on top of red
+        (WIDTH * 5 / 8, HEIGHT * 7 / 8),
+        // blue on top of the background
+        (WIDTH * 5 / 8, HEIGHT / 4),
+        // the opaque rectangle
+        (10, 5),
+    ];
+    for &point in samples.iter() {
+        assert_color_eq(
+            orbtk_pixel(&render_ctx, WIDTH, point),
+            bitmap_pixel(&buffer, WIDTH, point),
+            2,
+            &format!("pixel {:?}", point),
+        );
+    }
+}
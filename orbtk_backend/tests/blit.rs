@@ -0,0 +1,87 @@
+use orbtk_backend::{OffscreenCanvas, OrbtkError};
+use plotters::prelude::*;
+
+/// A `w` x `h` RGB bitmap where every pixel encodes its own coordinates.
+fn pattern(w: u32, h: u32) -> Vec<u8> {
+    (0..h)
+        .flat_map(|y| (0..w).flat_map(move |x| vec![x as u8 * 20, y as u8 * 20, 100]))
+        .collect()
+}
+
+/// Blits a 10x10 pattern at `pos` onto a transparent 20x20 canvas and checks every pixel: the
+/// visible part of the bitmap lands at its offset, everything else stays untouched.
+fn check_blit(pos: (i32, i32)) {
+    let mut canvas = OffscreenCanvas::new(20, 20).unwrap();
+    canvas
+        .backend()
+        .unwrap()
+        .blit_bitmap(pos, (10, 10), &pattern(10, 10))
+        .unwrap();
+
+    let rgba = canvas.to_rgba();
+    for y in 0..20 {
+        for x in 0..20 {
+            let idx = ((y * 20 + x) * 4) as usize;
+            let (sx, sy) = (x - pos.0, y - pos.1);
+            let expected = if (0..10).contains(&sx) && (0..10).contains(&sy) {
+                [sx as u8 * 20, sy as u8 * 20, 100, 255]
+            } else {
+                [0, 0, 0, 0]
+            };
+            assert_eq!(
+                &rgba[idx..idx + 4],
+                &expected[..],
+                "pixel ({}, {}) of a bitmap at {:?}",
+                x,
+                y,
+                pos
+            );
+        }
+    }
+}
+
+#[test]
+fn bitmap_is_clipped_to_the_canvas() {
+    check_blit((5, 5));
+    check_blit((15, -5));
+    check_blit((-3, 14));
+    check_blit((-10, 0));
+    check_blit((20, 20));
+}
+
+#[test]
+fn short_bitmap_is_rejected() {
+    let mut canvas = OffscreenCanvas::new(20, 20).unwrap();
+    let result = canvas
+        .backend()
+        .unwrap()
+        .blit_bitmap((0, 0), (10, 10), &pattern(10, 9));
+    assert!(matches!(
+        result,
+        Err(DrawingErrorKind::DrawingError(OrbtkError::InvalidBitmap {
+            width: 10,
+            height: 10,
+            len: 270,
+        }))
+    ));
+}
+
+#[test]
+fn huge_bitmaps_dont_overflow() {
+    let mut canvas = OffscreenCanvas::new(20, 20).unwrap();
+    let mut backend = canvas.backend().unwrap();
+
+    // 3 bytes per pixel of this size don't fit into a u32.
+    let result = backend.blit_bitmap((0, 0), (100_000, 100_000), &pattern(1, 1));
+    match result {
+        Err(DrawingErrorKind::DrawingError(e @ OrbtkError::InvalidBitmap { .. })) => {
+            assert!(e.to_string().contains("30000000000 bytes"), "{}", e)
+        }
+        other => panic!("expected an invalid bitmap error, got {:?}", other),
+    }
+
+    // The right edge of the bitmap lies beyond i32::MAX.
+    backend
+        .blit_bitmap((i32::MAX - 5, 0), (10, 10), &pattern(10, 10))
+        .unwrap();
+}
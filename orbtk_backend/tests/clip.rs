@@ -0,0 +1,91 @@
+use orbtk_backend::{ClipStack, OffscreenCanvas};
+use plotters::prelude::*;
+
+#[test]
+fn empty_stack_does_not_clip() {
+    assert_eq!(ClipStack::default().effective(), None);
+}
+
+#[test]
+fn nested_rects_intersect() {
+    let clip = ClipStack::default();
+    let _outer = clip.push((0, 0), (100, 80));
+    {
+        let _inner = clip.push_range((20..150, 10..40));
+        assert_eq!(clip.effective(), Some(((20, 10), (100, 40))));
+    }
+    assert_eq!(clip.effective(), Some(((0, 0), (100, 80))));
+}
+
+#[test]
+fn dropping_a_guard_pops_everything_pushed_after_it() {
+    let clip = ClipStack::default();
+    let outer = clip.push((0, 0), (100, 80));
+    let inner = clip.push((10, 10), (20, 20));
+    drop(outer);
+    assert_eq!(clip.effective(), None);
+    drop(inner);
+    assert_eq!(clip.effective(), None);
+}
+
+#[test]
+fn disjoint_rects_clip_everything() {
+    let clip = ClipStack::default();
+    let _first = clip.push((0, 0), (10, 10));
+    let _second = clip.push((20, 20), (30, 30));
+    let (upper_left, bottom_right) = clip.effective().unwrap();
+    assert_eq!(upper_left, bottom_right);
+}
+
+#[test]
+fn handles_share_the_stack() {
+    let clip = ClipStack::default();
+    let handle = clip.clone();
+    let _guard = handle.push((5, 5), (10, 10));
+    assert_eq!(clip.effective(), Some(((5, 5), (10, 10))));
+    clip.clear();
+    assert_eq!(handle.effective(), None);
+}
+
+#[test]
+fn series_stay_inside_the_plotting_area() {
+    let mut canvas = OffscreenCanvas::new(120, 100).unwrap();
+    let (x_range, y_range) = {
+        let backend = canvas.backend().unwrap();
+        let clip = backend.clip_stack();
+        let root = backend.into_drawing_area();
+        root.fill(&WHITE).unwrap();
+        let mut chart = ChartBuilder::on(&root)
+            .margin(20)
+            .build_cartesian_2d(0.0..10.0, 0.0..10.0)
+            .unwrap();
+        let range = chart.plotting_area().get_pixel_range();
+
+        let _plot_clip = clip.push_range(range.clone());
+        // Overshoots the coordinate range at both ends.
+        chart
+            .draw_series(LineSeries::new(
+                vec![(-5.0, -5.0), (15.0, 15.0)],
+                RED.stroke_width(3),
+            ))
+            .unwrap();
+        range
+    };
+
+    let rgba = canvas.to_rgba();
+    let mut inside = 0;
+    for (i, pixel) in rgba.chunks(4).enumerate() {
+        let (x, y) = ((i % 120) as i32, (i / 120) as i32);
+        if pixel[0] > 200 && pixel[1] < 100 {
+            assert!(
+                x_range.contains(&x) && y_range.contains(&y),
+                "red at ({}, {}) outside {:?}",
+                x,
+                y,
+                (&x_range, &y_range)
+            );
+            inside += 1;
+        }
+    }
+    assert!(inside > 0, "the series wasn't drawn");
+}
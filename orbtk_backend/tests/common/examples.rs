@@ -0,0 +1,214 @@
+use orbtk_backend::ClipStack;
+use plotters::coord::Shift;
+use plotters::prelude::{
+    ChartBuilder, Circle, Color, DiscreteRanged, DrawingArea, DrawingAreaErrorKind, DrawingBackend,
+    EmptyElement, Histogram, IntoFont, IntoLinspace, IntoSegmentedCoord, LineSeries, PathElement,
+    PointSeries, Polygon, ShapeStyle, Text, BLACK, BLUE, RED, WHITE,
+};
+
+/// Font family used by the examples. OrbTk resolves it to the bundled Roboto, other plotters
+/// backends to a system font.
+pub const FONT: &str = "sans-serif";
+
+/// Number of examples `draw` knows about.
+pub const EXAMPLES: u32 = 4;
+
+/// Draws example chart `example` onto `root`. Series are clipped to the plotting area through
+/// `clip`, which has no effect on backends other than `OrbtkBackend`.
+///
+/// Shared by the example application and the tests of `orbtk_backend`.
+pub fn draw<DB: DrawingBackend>(
+    example: u32,
+    root: &DrawingArea<DB, Shift>,
+    clip: &ClipStack,
+) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+    root.fill(&WHITE)?;
+    if example == 1 {
+        let mut chart = ChartBuilder::on(root)
+            .x_label_area_size(40)
+            .y_label_area_size(40)
+            .margin(5)
+            .caption("Histogram Test", (FONT, 50))
+            .build_cartesian_2d((0u32..10u32).into_segmented(), 0u32..10u32)?;
+        chart
+            .configure_mesh()
+            .disable_x_mesh()
+            .bold_line_style(&WHITE.mix(0.3))
+            .y_desc("Count")
+            .x_desc("Bucket")
+            .axis_desc_style((FONT, 15))
+            .draw()?;
+
+        let data = [
+            0u32, 1, 1, 1, 4, 2, 5, 7, 8, 6, 4, 2, 1, 8, 3, 3, 3, 4, 4, 3, 3, 3,
+        ];
+
+        let _plot_clip = clip.push_range(chart.plotting_area().get_pixel_range());
+        chart.draw_series(
+            Histogram::vertical(&chart)
+                .style(RED.mix(0.5).filled())
+                .data(data.iter().map(|x: &u32| (*x, 1))),
+        )?;
+    } else if example == 2 {
+        root.fill(&WHITE)?;
+        let root = root.margin(10, 10, 10, 10);
+        // After this point, we should be able to draw construct a chart context
+        let mut chart = ChartBuilder::on(&root)
+            // Set the caption of the chart
+            .caption("This is our first plot", (FONT, 40).into_font())
+            // Set the size of the label region
+            .x_label_area_size(20)
+            .y_label_area_size(40)
+            // Finally attach a coordinate on the drawing area and make a chart context
+            .build_cartesian_2d(0f32..10f32, 0f32..10f32)?;
+
+        // Then we can draw a mesh
+        chart
+            .configure_mesh()
+            // We can customize the maximum number of labels allowed for each axis
+            .x_labels(5)
+            .y_labels(5)
+            // We can also change the format of the label text
+            .y_label_formatter(&|x| format!("{:.3}", x))
+            .draw()?;
+
+        // And we can draw something in the drawing area
+        let _plot_clip = clip.push_range(chart.plotting_area().get_pixel_range());
+        chart.draw_series(LineSeries::new(
+            vec![(0.0, 0.0), (5.0, 5.0), (8.0, 7.0)],
+            &RED,
+        ))?;
+        // Similarly, we can draw point series
+        chart.draw_series(PointSeries::of_element(
+            vec![(0.0, 0.0), (5.0, 5.0), (8.0, 7.0)],
+            5,
+            &RED,
+            &|c, s, st| {
+                EmptyElement::at(c)    // We want to construct a composed element on-the-fly
+                        + Circle::new((0, 0), s, st.filled()) // At this point, the new pixel coordinate is established
+                        + Text::new(format!("{:?}", c), (10, 0), (FONT, 10).into_font())
+            },
+        ))?;
+    } else if example == 3 {
+        let mut chart = ChartBuilder::on(root)
+            .caption("Koch's Snowflake", (FONT, 50))
+            .build_cartesian_2d(-2.0..2.0, -1.5..1.5)?;
+
+        let mut snowflake_vertices = {
+            let mut current: Vec<(f64, f64)> = vec![
+                (0.0, 1.0),
+                ((3.0f64).sqrt() / 2.0, -0.5),
+                (-(3.0f64).sqrt() / 2.0, -0.5),
+            ];
+            for _ in 0..6 {
+                current = snowflake_iter(&current[..]);
+            }
+            current
+        };
+
+        chart.draw_series(std::iter::once(Polygon::new(
+            snowflake_vertices.clone(),
+            &RED.mix(0.2),
+        )))?;
+        snowflake_vertices.push(snowflake_vertices[0]);
+        chart.draw_series(std::iter::once(PathElement::new(snowflake_vertices, &RED)))?;
+    } else if example == 4 {
+        root.fill(&WHITE)?;
+
+        let root = root.titled("Image Title", (FONT, 30))?;
+
+        let (upper, lower) = root.split_vertically(512);
+
+        let x_axis = (-3.4f32..3.4).step(0.1);
+
+        let mut cc = ChartBuilder::on(&upper)
+            .margin(5)
+            .set_all_label_area_size(50)
+            .caption("Sine and Cosine", (FONT, 20))
+            .build_cartesian_2d(-3.4f32..3.4, -1.2f32..1.2f32)?;
+
+        cc.configure_mesh()
+            .x_labels(20)
+            .y_labels(10)
+            .disable_mesh()
+            .x_label_formatter(&|v| format!("{:.1}", v))
+            .y_label_formatter(&|v| format!("{:.1}", v))
+            .draw()?;
+
+        let plot_clip = clip.push_range(cc.plotting_area().get_pixel_range());
+        cc.draw_series(LineSeries::new(x_axis.values().map(|x| (x, x.sin())), &RED))?
+            .label("Sine")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], &RED));
+
+        cc.draw_series(LineSeries::new(
+            x_axis.values().map(|x| (x, x.cos())),
+            &BLUE,
+        ))?
+        .label("Cosine")
+        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], &BLUE));
+
+        // The legend is drawn over the plotting area's border.
+        drop(plot_clip);
+
+        cc.configure_series_labels().border_style(&BLACK).draw()?;
+
+        /*
+        // It's possible to use a existing pointing element
+         cc.draw_series(PointSeries::<_, _, Circle<_>>::new(
+            (-3.0f32..2.1f32).step(1.0).values().map(|x| (x, x.sin())),
+            5,
+            Into::<ShapeStyle>::into(&RGBColor(255,0,0)).filled(),
+        ))?;*/
+
+        // Otherwise you can use a function to construct your pointing element yourself
+        cc.draw_series(PointSeries::of_element(
+            (-3.0f32..2.1f32).step(1.0).values().map(|x| (x, x.sin())),
+            5,
+            ShapeStyle::from(&RED).filled(),
+            &|coord, size, style| {
+                EmptyElement::at(coord)
+                    + Circle::new((0, 0), size, style)
+                    + Text::new(format!("{:?}", coord), (0, 15), (FONT, 15))
+            },
+        ))?;
+
+        let drawing_areas = lower.split_evenly((1, 2));
+
+        for (drawing_area, idx) in drawing_areas.iter().zip(1..) {
+            let mut cc = ChartBuilder::on(&drawing_area)
+                .x_label_area_size(30)
+                .y_label_area_size(30)
+                .margin_right(20)
+                .caption(format!("y = x^{}", 1 + 2 * idx), (FONT, 20))
+                .build_cartesian_2d(-1f32..1f32, -1f32..1f32)?;
+            cc.configure_mesh().x_labels(5).y_labels(3).draw()?;
+
+            let _plot_clip = clip.push_range(cc.plotting_area().get_pixel_range());
+            cc.draw_series(LineSeries::new(
+                (-1f32..1f32)
+                    .step(0.01)
+                    .values()
+                    .map(|x| (x, x.powf(idx as f32 * 2.0 + 1.0))),
+                &BLUE,
+            ))?;
+        }
+    }
+    Ok(())
+}
+
+fn snowflake_iter(points: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    let mut ret = vec![];
+    for i in 0..points.len() {
+        let (start, end) = (points[i], points[(i + 1) % points.len()]);
+        let t = ((end.0 - start.0) / 3.0, (end.1 - start.1) / 3.0);
+        let s = (
+            t.0 * 0.5 - t.1 * (0.75f64).sqrt(),
+            t.1 * 0.5 + (0.75f64).sqrt() * t.0,
+        );
+        ret.push(start);
+        ret.push((start.0 + t.0, start.1 + t.1));
+        ret.push((start.0 + t.0 + s.0, start.1 + t.1 + s.1));
+        ret.push((start.0 + t.0 * 2.0, start.1 + t.1 * 2.0));
+    }
+    ret
+}
@@ -0,0 +1,59 @@
+#![allow(dead_code)]
+
+pub mod examples;
+
+use orbtk::prelude::RenderContext2D;
+use orbtk_backend::{OffscreenCanvas, OrbtkBackend};
+use plotters_backend::{FontFamily, FontStyle};
+
+static ROBOTO: &[u8] = include_bytes!("../../src/Roboto-Regular.ttf");
+
+/// The data of Roboto, for tests that register it themselves so they also run without the
+/// `bundled-font` feature.
+pub fn roboto() -> &'static [u8] {
+    ROBOTO
+}
+
+/// Returns a backend drawing onto `canvas` with Roboto registered as the normal sans-serif font.
+pub fn backend_with_roboto(canvas: &mut OffscreenCanvas) -> OrbtkBackend<'_> {
+    let mut backend = canvas.backend().unwrap();
+    backend
+        .register_font(FontFamily::SansSerif, FontStyle::Normal, "Roboto", ROBOTO)
+        .unwrap();
+    backend
+}
+
+/// Returns the unpremultiplied RGB value of a pixel of an OrbTk render context.
+pub fn orbtk_pixel(render_ctx: &RenderContext2D, width: u32, (x, y): (u32, u32)) -> (u8, u8, u8) {
+    let argb = render_ctx.data()[(y * width + x) as usize];
+    let a = argb >> 24;
+    let channel = |shift: u32| {
+        let c = (argb >> shift) & 0xff;
+        if a == 0 {
+            0
+        } else {
+            (c * 255 / a).min(255) as u8
+        }
+    };
+    (channel(16), channel(8), channel(0))
+}
+
+/// Returns the RGB value of a pixel of a plotters `BitMapBackend` buffer.
+pub fn bitmap_pixel(buffer: &[u8], width: u32, (x, y): (u32, u32)) -> (u8, u8, u8) {
+    let idx = ((y * width + x) * 3) as usize;
+    (buffer[idx], buffer[idx + 1], buffer[idx + 2])
+}
+
+/// Asserts that two colors differ by at most `tolerance` in every channel.
+pub fn assert_color_eq(actual: (u8, u8, u8), expected: (u8, u8, u8), tolerance: u8, what: &str) {
+    let diff = |a: u8, b: u8| a.abs_diff(b);
+    assert!(
+        diff(actual.0, expected.0) <= tolerance
+            && diff(actual.1, expected.1) <= tolerance
+            && diff(actual.2, expected.2) <= tolerance,
+        "{}: got {:?}, expected {:?}",
+        what,
+        actual,
+        expected
+    );
+}
@@ -0,0 +1,42 @@
+use orbtk_backend::LineDash;
+
+#[test]
+fn invalid_patterns_are_solid() {
+    assert!(LineDash::default().is_solid());
+    assert!(LineDash::new(&[], 0.0).is_solid());
+    assert!(LineDash::new(&[0.0, 0.0], 0.0).is_solid());
+    assert!(LineDash::new(&[4.0, -1.0], 0.0).is_solid());
+    assert!(LineDash::new(&[4.0, f64::NAN], 0.0).is_solid());
+    assert!(!LineDash::new(&[4.0, 2.0], 0.0).is_solid());
+}
+
+#[test]
+fn dashes_continue_across_segments() {
+    let dash = LineDash::new(&[4.0, 2.0], 0.0);
+    // The first gap wraps around the corner at x = 5.
+    let dashes = dash.apply(&[(0.0, 0.0), (5.0, 0.0), (5.0, 7.0)]);
+    assert_eq!(
+        dashes,
+        vec![vec![(0.0, 0.0), (4.0, 0.0)], vec![(5.0, 1.0), (5.0, 5.0)]]
+    );
+}
+
+#[test]
+fn offset_shifts_the_pattern() {
+    let dash = LineDash::new(&[4.0, 2.0], 5.0);
+    let dashes = dash.apply(&[(0.0, 0.0), (10.0, 0.0)]);
+    assert_eq!(
+        dashes,
+        vec![vec![(1.0, 0.0), (5.0, 0.0)], vec![(7.0, 0.0), (10.0, 0.0)]]
+    );
+}
+
+#[test]
+fn odd_patterns_are_repeated() {
+    let dash = LineDash::new(&[3.0], 0.0);
+    let dashes = dash.apply(&[(0.0, 0.0), (0.0, 10.0)]);
+    assert_eq!(
+        dashes,
+        vec![vec![(0.0, 0.0), (0.0, 3.0)], vec![(0.0, 6.0), (0.0, 9.0)]]
+    );
+}
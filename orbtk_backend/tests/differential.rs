@@ -0,0 +1,305 @@
+//! Draws the same plotters scenes through `OrbtkBackend` and `BitMapBackend` and compares the
+//! structure of the results: where ink ends up, where text is anchored and which colors are used.
+//! Pixel exact comparisons aren't possible as the rasterizers differ.
+//!
+//! `BitMapBackend` draws text with whatever font `sans-serif` is on the machine, if there is one
+//! at all, while OrbTk uses Roboto. Only scenes without text have to match, differences in the
+//! scenes with text are reported on stderr.
+
+mod common;
+
+use std::collections::{HashMap, HashSet};
+
+#[cfg(feature = "bundled-font")]
+use common::examples;
+use orbtk_backend::{ClipStack, OffscreenCanvas, OrbtkBackend, OrbtkError};
+use plotters::coord::Shift;
+use plotters::prelude::*;
+#[cfg(feature = "bundled-font")]
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+#[cfg(feature = "bundled-font")]
+use plotters::style::FontTransform;
+
+/// Largest distance in pixels between the edges of inked regions.
+const MAX_EDGE_DISTANCE: i32 = 8;
+/// Largest share of pixels of a region that may fall into different color buckets.
+const MAX_HISTOGRAM_DISTANCE: f64 = 0.05;
+/// Side length of the regions that are compared on their own, so a missing series or a moved
+/// caption isn't averaged out by the rest of a large chart.
+const REGION_SIZE: u32 = 100;
+/// Regions with fewer inked pixels count as empty, as glyph edges may reach into a region with
+/// one font but not with the other.
+const MIN_INK: usize = 10;
+
+struct Rendered {
+    width: u32,
+    height: u32,
+    rgb: Vec<(u8, u8, u8)>,
+}
+
+impl Rendered {
+    /// Bounding box `(x0, y0, x1, y1)` of all pixels that aren't white, `None` if there are
+    /// fewer than `MIN_INK` of them.
+    fn ink_bounds(&self) -> Option<(i32, i32, i32, i32)> {
+        let mut bounds: Option<(i32, i32, i32, i32)> = None;
+        let mut inked = 0;
+        for (index, &pixel) in self.rgb.iter().enumerate() {
+            // Anti-aliasing leaves faint pixels, only count clearly inked ones.
+            if pixel.0 > 224 && pixel.1 > 224 && pixel.2 > 224 {
+                continue;
+            }
+            inked += 1;
+            let index = index as u32;
+            let (x, y) = ((index % self.width) as i32, (index / self.width) as i32);
+            bounds = Some(match bounds {
+                Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x + 1), y1.max(y + 1)),
+                None => (x, y, x + 1, y + 1),
+            });
+        }
+        bounds.filter(|_| inked >= MIN_INK)
+    }
+
+    /// The `width` x `height` pixels at `(x, y)`.
+    fn region(&self, (x, y): (u32, u32), (width, height): (u32, u32)) -> Rendered {
+        let rgb = (y..y + height)
+            .flat_map(|row| {
+                let start = (row * self.width + x) as usize;
+                self.rgb[start..start + width as usize].iter().copied()
+            })
+            .collect();
+        Rendered { width, height, rgb }
+    }
+
+    /// Splits the image into regions of at most `REGION_SIZE` pixels square.
+    fn regions(&self) -> Vec<((u32, u32), Rendered)> {
+        let mut regions = vec![];
+        for y in (0..self.height).step_by(REGION_SIZE as usize) {
+            for x in (0..self.width).step_by(REGION_SIZE as usize) {
+                let size = (
+                    REGION_SIZE.min(self.width - x),
+                    REGION_SIZE.min(self.height - y),
+                );
+                regions.push(((x, y), self.region((x, y), size)));
+            }
+        }
+        regions
+    }
+
+    /// Number of pixels per color, quantized to 8 levels per channel.
+    fn histogram(&self) -> HashMap<(u8, u8, u8), usize> {
+        let mut histogram = HashMap::new();
+        for &(r, g, b) in self.rgb.iter() {
+            *histogram.entry((r >> 5, g >> 5, b >> 5)).or_insert(0) += 1;
+        }
+        histogram
+    }
+}
+
+fn render_orbtk<F>((width, height): (u32, u32), draw: F) -> Rendered
+where
+    F: Fn(
+        &DrawingArea<OrbtkBackend, Shift>,
+        &ClipStack,
+    ) -> Result<(), DrawingAreaErrorKind<OrbtkError>>,
+{
+    let mut canvas = OffscreenCanvas::new(width, height).unwrap();
+    {
+        let backend = canvas.backend().unwrap();
+        let clip = backend.clip_stack();
+        draw(&backend.into_drawing_area(), &clip).unwrap();
+    }
+    let rgb = canvas
+        .to_rgba()
+        .chunks(4)
+        .map(|p| (p[0], p[1], p[2]))
+        .collect();
+    Rendered { width, height, rgb }
+}
+
+type BitMapError = <BitMapBackend<'static> as DrawingBackend>::ErrorType;
+
+/// Fails if `BitMapBackend` can't draw the scene, e.g. because there's no system font for text.
+fn render_bitmap<F>((width, height): (u32, u32), draw: F) -> Result<Rendered, String>
+where
+    F: Fn(
+        &DrawingArea<BitMapBackend, Shift>,
+        &ClipStack,
+    ) -> Result<(), DrawingAreaErrorKind<BitMapError>>,
+{
+    let mut buffer = vec![0u8; (width * height * 3) as usize];
+    {
+        let root = BitMapBackend::with_buffer(&mut buffer, (width, height)).into_drawing_area();
+        draw(&root, &ClipStack::default()).map_err(|e| e.to_string())?;
+        root.present().map_err(|e| e.to_string())?;
+    }
+    let rgb = buffer.chunks(3).map(|p| (p[0], p[1], p[2])).collect();
+    Ok(Rendered { width, height, rgb })
+}
+
+/// Lists the structural differences between the two renderings that exceed the thresholds, for
+/// the whole image and for each region of it.
+fn compare(orbtk: &Rendered, bitmap: &Rendered) -> Vec<String> {
+    let mut differences = compare_image(orbtk, bitmap);
+    for ((position, a), (_, b)) in orbtk.regions().iter().zip(bitmap.regions().iter()) {
+        differences.extend(
+            compare_image(a, b)
+                .into_iter()
+                .map(|difference| format!("region at {:?}: {}", position, difference)),
+        );
+    }
+    differences
+}
+
+fn compare_image(orbtk: &Rendered, bitmap: &Rendered) -> Vec<String> {
+    let mut differences = vec![];
+
+    match (orbtk.ink_bounds(), bitmap.ink_bounds()) {
+        (Some(a), Some(b)) => {
+            let edges = [
+                ("left", a.0, b.0),
+                ("top", a.1, b.1),
+                ("right", a.2, b.2),
+                ("bottom", a.3, b.3),
+            ];
+            for &(edge, a, b) in edges.iter() {
+                if (a - b).abs() > MAX_EDGE_DISTANCE {
+                    differences.push(format!("{} edge of ink at {} instead of {}", edge, a, b));
+                }
+            }
+        }
+        (a, b) => {
+            if a.is_some() != b.is_some() {
+                differences.push(format!("ink bounds {:?} instead of {:?}", a, b));
+            }
+        }
+    }
+
+    let (a, b) = (orbtk.histogram(), bitmap.histogram());
+    let moved: usize = a
+        .keys()
+        .chain(b.keys())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .map(|color| {
+            let (x, y) = (a.get(color).unwrap_or(&0), b.get(color).unwrap_or(&0));
+            x.abs_diff(*y)
+        })
+        .sum();
+    let distance = moved as f64 / (2 * orbtk.rgb.len()) as f64;
+    if distance > MAX_HISTOGRAM_DISTANCE {
+        differences.push(format!(
+            "{:.1}% of the pixels have different colors",
+            distance * 100.0
+        ));
+    }
+
+    differences
+}
+
+fn assert_similar(scene: &str, orbtk: Rendered, bitmap: Result<Rendered, String>) {
+    let differences = compare(&orbtk, &bitmap.unwrap());
+    assert!(
+        differences.is_empty(),
+        "{} differs from BitMapBackend:\n  {}",
+        scene,
+        differences.join("\n  ")
+    );
+}
+
+/// Prints the differences of a scene with text, as the fonts of the two backends differ.
+#[cfg(feature = "bundled-font")]
+fn report_differences(scene: &str, orbtk: Rendered, bitmap: Result<Rendered, String>) {
+    match bitmap {
+        Ok(bitmap) => {
+            for difference in compare(&orbtk, &bitmap) {
+                eprintln!("{} differs from BitMapBackend: {}", scene, difference);
+            }
+        }
+        Err(e) => eprintln!("{} can't be drawn by BitMapBackend: {}", scene, e),
+    }
+}
+
+/// A chart without any text: a line series, circles and a semi-transparent rectangle.
+fn draw_shapes<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    clip: &ClipStack,
+) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+    root.fill(&WHITE)?;
+    let mut chart = ChartBuilder::on(root)
+        .margin(20)
+        .build_cartesian_2d(0f32..10f32, 0f32..10f32)?;
+    let _plot_clip = clip.push_range(chart.plotting_area().get_pixel_range());
+    chart.draw_series(LineSeries::new(
+        (0..=100).map(|x| (x as f32 / 10.0, 5.0 + 4.0 * (x as f32 / 10.0).sin())),
+        &RED,
+    ))?;
+    chart.draw_series((1..10).map(|x| Circle::new((x as f32, x as f32), 5, BLUE.filled())))?;
+    chart.draw_series(std::iter::once(Rectangle::new(
+        [(1f32, 1f32), (4f32, 3f32)],
+        GREEN.mix(0.5).filled(),
+    )))?;
+    Ok(())
+}
+
+#[test]
+fn shapes_match_bitmap_backend() {
+    assert_similar(
+        "shapes",
+        render_orbtk((400, 300), draw_shapes),
+        render_bitmap((400, 300), draw_shapes),
+    );
+}
+
+#[test]
+#[cfg(feature = "bundled-font")]
+fn examples_are_compared_with_bitmap_backend() {
+    for example in 1..=examples::EXAMPLES {
+        report_differences(
+            &format!("example {}", example),
+            render_orbtk((1200, 800), |root, clip| {
+                examples::draw(example, root, clip)
+            }),
+            render_bitmap((1200, 800), |root, clip| {
+                examples::draw(example, root, clip)
+            }),
+        );
+    }
+}
+
+#[cfg(feature = "bundled-font")]
+fn draw_label<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    transform: &FontTransform,
+    pos: Pos,
+) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+    root.fill(&WHITE)?;
+    let style = (examples::FONT, 20)
+        .into_font()
+        .transform(transform.clone())
+        .color(&BLACK)
+        .pos(pos);
+    root.draw_text("Placement", &style, (100, 100))
+}
+
+#[test]
+#[cfg(feature = "bundled-font")]
+fn text_placement_is_compared_with_bitmap_backend() {
+    let transforms = [
+        FontTransform::None,
+        FontTransform::Rotate90,
+        FontTransform::Rotate180,
+        FontTransform::Rotate270,
+    ];
+    for transform in transforms.iter() {
+        for &h_pos in [HPos::Left, HPos::Center, HPos::Right].iter() {
+            for &v_pos in [VPos::Top, VPos::Center, VPos::Bottom].iter() {
+                let pos = Pos::new(h_pos, v_pos);
+                report_differences(
+                    &format!("text {:?} {:?} {:?}", transform, h_pos, v_pos),
+                    render_orbtk((200, 200), |root, _| draw_label(root, transform, pos)),
+                    render_bitmap((200, 200), |root, _| draw_label(root, transform, pos)),
+                );
+            }
+        }
+    }
+}
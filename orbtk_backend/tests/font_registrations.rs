@@ -0,0 +1,22 @@
+use orbtk::prelude::RenderContext2D;
+use orbtk_backend::{font_registrations, OrbtkBackend};
+
+// The counter is shared by the whole process, so this is the only test in the file.
+#[test]
+fn backends_on_the_same_context_register_fonts_once() {
+    let mut render_ctx = RenderContext2D::new(10.0, 10.0);
+
+    let before = font_registrations();
+    drop(OrbtkBackend::new(&mut render_ctx, 10.0, 10.0).unwrap());
+    let first = font_registrations() - before;
+    let expected = if cfg!(feature = "bundled-font") { 1 } else { 0 };
+    assert_eq!(first, expected);
+
+    drop(OrbtkBackend::new(&mut render_ctx, 10.0, 10.0).unwrap());
+    assert_eq!(font_registrations() - before, first);
+
+    // A new context gets the fonts registered again.
+    let mut other = RenderContext2D::new(10.0, 10.0);
+    drop(OrbtkBackend::new(&mut other, 10.0, 10.0).unwrap());
+    assert_eq!(font_registrations() - before, 2 * first);
+}
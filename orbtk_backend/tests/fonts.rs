@@ -0,0 +1,187 @@
+mod common;
+
+use common::roboto;
+use orbtk::prelude::RenderContext2D;
+use orbtk_backend::{register_font, FontRegistry, OrbtkError};
+#[cfg(feature = "bundled-font")]
+use orbtk_backend::{OffscreenCanvas, DEFAULT_FONT};
+#[cfg(feature = "bundled-font")]
+use plotters::prelude::*;
+use plotters_backend::{FontFamily, FontStyle};
+
+#[test]
+#[cfg(feature = "bundled-font")]
+fn shared_fonts_reach_new_backends() {
+    register_font(
+        FontFamily::Monospace,
+        FontStyle::Normal,
+        "Roboto-Copy",
+        roboto(),
+    )
+    .unwrap();
+
+    for _ in 0..2 {
+        let mut canvas = OffscreenCanvas::new(10, 10).unwrap();
+        let mut backend = canvas.backend().unwrap();
+        let fonts = backend.fonts_mut();
+        assert_eq!(
+            fonts.resolve(FontFamily::Monospace, FontStyle::Normal),
+            "Roboto-Copy"
+        );
+        assert_eq!(
+            fonts.resolve(FontFamily::Serif, FontStyle::Normal),
+            "Roboto-Regular"
+        );
+    }
+}
+
+#[test]
+fn invalid_font_data_is_rejected() {
+    let result = register_font(
+        FontFamily::Serif,
+        FontStyle::Normal,
+        "Broken",
+        b"not a font",
+    );
+    assert!(matches!(result, Err(OrbtkError::FontRegistration { .. })));
+}
+
+#[test]
+fn styles_fall_back_to_related_styles() {
+    let mut fonts = FontRegistry::default();
+    fonts.set_fallback("Fallback");
+    fonts.map_family(FontFamily::SansSerif, FontStyle::Normal, "Sans");
+    fonts.map_family(FontFamily::SansSerif, FontStyle::Italic, "Sans-Italic");
+    fonts.map_family(FontFamily::Serif, FontStyle::Normal, "Serif");
+    fonts.map_family(FontFamily::Serif, FontStyle::Oblique, "Serif-Oblique");
+    fonts.map_family(FontFamily::Monospace, FontStyle::Bold, "Mono-Bold");
+
+    let cases = [
+        // Italic and oblique stand in for each other, before the normal style.
+        (FontFamily::SansSerif, FontStyle::Italic, "Sans-Italic"),
+        (FontFamily::SansSerif, FontStyle::Oblique, "Sans-Italic"),
+        (FontFamily::Serif, FontStyle::Oblique, "Serif-Oblique"),
+        (FontFamily::Serif, FontStyle::Italic, "Serif-Oblique"),
+        // Bold falls back to normal only.
+        (FontFamily::SansSerif, FontStyle::Bold, "Sans"),
+        (FontFamily::Serif, FontStyle::Bold, "Serif"),
+        (FontFamily::SansSerif, FontStyle::Normal, "Sans"),
+        (FontFamily::Monospace, FontStyle::Bold, "Mono-Bold"),
+        (FontFamily::Monospace, FontStyle::Normal, "Fallback"),
+        (FontFamily::Monospace, FontStyle::Italic, "Fallback"),
+        // Families without any font use the fallback in every style.
+        (FontFamily::Name("Unknown"), FontStyle::Normal, "Fallback"),
+        (FontFamily::Name("Unknown"), FontStyle::Italic, "Fallback"),
+        (FontFamily::Name("Unknown"), FontStyle::Oblique, "Fallback"),
+        (FontFamily::Name("Unknown"), FontStyle::Bold, "Fallback"),
+    ];
+    for &(family, style, expected) in cases.iter() {
+        assert_eq!(
+            fonts.resolve(family, style),
+            expected,
+            "{} {}",
+            family.as_str(),
+            style.as_str()
+        );
+    }
+}
+
+#[test]
+#[cfg(feature = "bundled-font")]
+fn text_box_follows_font_metrics() {
+    let mut canvas = OffscreenCanvas::new(100, 60).unwrap();
+    let (width, _) = canvas.size();
+    let mut backend = canvas.backend().unwrap();
+    let style = ("sans-serif", 20).into_font().color(&BLACK);
+
+    let metrics = backend.font_metrics(FontFamily::SansSerif, FontStyle::Normal, 20.0);
+    assert!(metrics.ascent > metrics.descent && metrics.descent > 0.0);
+    assert!((metrics.height() - 20.0).abs() < 1e-9);
+
+    // The box doesn't depend on which characters are drawn.
+    let heights: Vec<u32> = ["a", "Mg", "0.5", "_"]
+        .iter()
+        .map(|text| backend.estimate_text_size(text, &style).unwrap().1)
+        .collect();
+    assert!(heights.iter().all(|&h| h == metrics.height().ceil() as u32));
+
+    backend.draw_text("Mg", &style, (10, 20)).unwrap();
+    drop(backend);
+
+    let rgba = canvas.to_rgba();
+    let inked: Vec<u32> = (0..rgba.len() / 4)
+        .filter(|&i| rgba[i * 4 + 3] > 0)
+        .map(|i| i as u32 / width)
+        .collect();
+    let (top, bottom) = (inked.iter().min().unwrap(), inked.iter().max().unwrap());
+    assert!(*top >= 20 && (*bottom as f64) <= 20.0 + metrics.height());
+}
+
+#[test]
+#[cfg(feature = "bundled-font")]
+fn text_is_split_into_runs_per_font() {
+    let mut render_ctx = RenderContext2D::new(1.0, 1.0);
+    let mut fonts = FontRegistry::default();
+    fonts
+        .add_glyph_fallback(&mut render_ctx, "Roboto-Fallback", roboto())
+        .unwrap();
+
+    // Roboto has Greek and the usual math symbols, so nothing falls back. No font has CJK, which
+    // stays with the text's font instead of being split off.
+    let text = "\u{3bc}s \u{b1} 2\u{b2} \u{65e5}\u{672c}";
+    assert_eq!(fonts.runs(text, DEFAULT_FONT), vec![(text, DEFAULT_FONT)]);
+
+    // Without data the coverage of a font is unknown, so it is trusted with every character.
+    assert_eq!(
+        fonts.runs("\u{65e5}", "Unknown"),
+        vec![("\u{65e5}", "Unknown")]
+    );
+    assert!(fonts.runs("", DEFAULT_FONT).is_empty());
+}
+
+/// Roboto with its `cmap` table renamed, so it has no glyph for any character while OrbTk still
+/// draws its missing glyph box. The new tag keeps the table directory sorted.
+fn roboto_without_cmap() -> &'static [u8] {
+    let mut bytes = roboto().to_vec();
+    let tables = u16::from_be_bytes([bytes[4], bytes[5]]) as usize;
+    let record = (0..tables)
+        .map(|table| 12 + 16 * table)
+        .find(|&record| &bytes[record..record + 4] == b"cmap")
+        .unwrap();
+    bytes[record + 3] = b'q';
+    Box::leak(bytes.into_boxed_slice())
+}
+
+#[test]
+fn missing_glyphs_are_split_into_fallback_runs() {
+    let mut render_ctx = RenderContext2D::new(1.0, 1.0);
+    let mut fonts = FontRegistry::default();
+    fonts
+        .register(
+            &mut render_ctx,
+            FontFamily::Name("Empty"),
+            FontStyle::Normal,
+            "Empty",
+            roboto_without_cmap(),
+        )
+        .unwrap();
+
+    // Without glyph fallbacks the text isn't split up.
+    assert_eq!(
+        fonts.runs("ab\u{65e5}c", "Empty"),
+        vec![("ab\u{65e5}c", "Empty")]
+    );
+
+    fonts
+        .add_glyph_fallback(&mut render_ctx, "Roboto-Fallback", roboto())
+        .unwrap();
+    // Latin letters come from Roboto, the CJK character no font has stays with the text's font.
+    assert_eq!(
+        fonts.runs("ab\u{65e5}c", "Empty"),
+        vec![
+            ("ab", "Roboto-Fallback"),
+            ("\u{65e5}", "Empty"),
+            ("c", "Roboto-Fallback")
+        ]
+    );
+}
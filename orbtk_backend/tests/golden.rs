@@ -0,0 +1,183 @@
+//! Renders each primitive into an offscreen canvas and compares it against the reference images
+//! in `tests/golden`.
+//!
+//! Run with `ORBTK_BACKEND_REGENERATE=1` to write the references from the current output. A
+//! missing reference fails the test.
+
+use std::path::{Path, PathBuf};
+
+use orbtk_backend::{OffscreenCanvas, OrbtkBackend};
+use plotters::prelude::*;
+#[cfg(feature = "bundled-font")]
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+#[cfg(feature = "bundled-font")]
+use plotters::style::FontTransform;
+use plotters_backend::DrawingBackend as _;
+
+const WIDTH: u32 = 120;
+const HEIGHT: u32 = 120;
+/// Largest difference allowed per color channel.
+const TOLERANCE: u8 = 8;
+
+fn reference_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden")
+        .join(format!("{}.png", name))
+}
+
+fn read_png(path: &Path) -> (u32, u32, Vec<u8>) {
+    let decoder = png::Decoder::new(std::fs::File::open(path).unwrap());
+    let (info, mut reader) = decoder.read_info().unwrap();
+    assert_eq!(info.color_type, png::ColorType::RGBA, "{:?}", path);
+    let mut data = vec![0; info.buffer_size()];
+    reader.next_frame(&mut data).unwrap();
+    (info.width, info.height, data)
+}
+
+/// Draws `draw` on a white canvas and compares the result with the reference image `name`.
+fn check<F: FnOnce(&mut OrbtkBackend)>(name: &str, draw: F) {
+    let mut canvas = OffscreenCanvas::new(WIDTH, HEIGHT).unwrap();
+    {
+        let mut backend = canvas.backend().unwrap();
+        backend
+            .draw_rect((0, 0), (WIDTH as i32, HEIGHT as i32), &WHITE, true)
+            .unwrap();
+        draw(&mut backend);
+    }
+
+    let path = reference_path(name);
+    if std::env::var_os("ORBTK_BACKEND_REGENERATE").is_some() {
+        canvas.save_png(&path).unwrap();
+        return;
+    }
+    assert!(
+        path.exists(),
+        "no reference image {}, generate it with ORBTK_BACKEND_REGENERATE=1",
+        path.display()
+    );
+
+    let (width, height, expected) = read_png(&path);
+    assert_eq!((width, height), canvas.size(), "size of {}", name);
+    let actual = canvas.to_rgba();
+    let mismatches = actual
+        .chunks(4)
+        .zip(expected.chunks(4))
+        .filter(|(a, e)| {
+            a.iter()
+                .zip(e.iter())
+                .any(|(a, e)| a.abs_diff(*e) > TOLERANCE)
+        })
+        .count();
+    if mismatches > 0 {
+        let failed = std::env::temp_dir().join(format!("orbtk_backend_{}.png", name));
+        canvas.save_png(&failed).unwrap();
+        panic!(
+            "{} pixels of {} differ from the reference, the output was written to {}",
+            mismatches,
+            name,
+            failed.display()
+        );
+    }
+}
+
+#[test]
+fn draw_line() {
+    check("draw_line", |backend| {
+        backend.draw_line((10, 10), (110, 60), &BLACK).unwrap();
+        backend
+            .draw_line((10, 110), (110, 80), &RED.stroke_width(5))
+            .unwrap();
+        backend
+            .draw_line((60, 10), (60, 110), &BLUE.mix(0.5).stroke_width(3))
+            .unwrap();
+    });
+}
+
+#[test]
+fn draw_rect_filled() {
+    check("draw_rect_filled", |backend| {
+        backend
+            .draw_rect((10, 10), (80, 60), &RED.filled(), true)
+            .unwrap();
+        backend
+            .draw_rect((40, 40), (110, 110), &BLUE.mix(0.5).filled(), true)
+            .unwrap();
+    });
+}
+
+#[test]
+fn draw_rect_outline() {
+    check("draw_rect_outline", |backend| {
+        backend
+            .draw_rect((10, 10), (80, 60), &BLACK, false)
+            .unwrap();
+        backend
+            .draw_rect((40, 40), (110, 110), &GREEN.stroke_width(4), false)
+            .unwrap();
+    });
+}
+
+#[test]
+fn draw_circle() {
+    check("draw_circle", |backend| {
+        backend
+            .draw_circle((35, 35), 25, &RED.filled(), true)
+            .unwrap();
+        backend
+            .draw_circle((85, 85), 25, &BLUE.stroke_width(3), false)
+            .unwrap();
+    });
+}
+
+#[test]
+fn fill_polygon() {
+    check("fill_polygon", |backend| {
+        backend
+            .fill_polygon(
+                vec![(60, 10), (110, 100), (10, 100), (60, 60)],
+                &MAGENTA.mix(0.7),
+            )
+            .unwrap();
+    });
+}
+
+#[test]
+fn draw_path() {
+    check("draw_path", |backend| {
+        backend
+            .draw_path(
+                vec![(10, 110), (30, 20), (60, 90), (90, 10), (110, 110)],
+                &BLACK.stroke_width(3),
+            )
+            .unwrap();
+    });
+}
+
+#[test]
+#[cfg(feature = "bundled-font")]
+fn draw_text() {
+    let rotations = [
+        ("none", FontTransform::None),
+        ("rotate90", FontTransform::Rotate90),
+        ("rotate180", FontTransform::Rotate180),
+        ("rotate270", FontTransform::Rotate270),
+    ];
+    for (suffix, transform) in rotations.iter() {
+        check(&format!("draw_text_{}", suffix), |backend| {
+            for (row, &v_pos) in [VPos::Top, VPos::Center, VPos::Bottom].iter().enumerate() {
+                for (col, &h_pos) in [HPos::Left, HPos::Center, HPos::Right].iter().enumerate() {
+                    let pos = (20 + col as i32 * 40, 20 + row as i32 * 40);
+                    // Mark the anchor point so misplaced text is easy to spot.
+                    backend.draw_pixel(pos, RED.to_backend_color()).unwrap();
+                    let style = ("Roboto-Regular", 12)
+                        .into_font()
+                        .transform(transform.clone())
+                        .color(&BLACK)
+                        .pos(Pos::new(h_pos, v_pos));
+                    backend.draw_text("Ag", &style, pos).unwrap();
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use common::{assert_color_eq, orbtk_pixel};
+use orbtk::prelude::RenderContext2D;
+use orbtk_backend::{Gradient, OrbtkBackend, OrbtkError};
+use plotters::prelude::*;
+use plotters_backend::{DrawingBackend as _, DrawingErrorKind};
+
+const WIDTH: u32 = 160;
+const HEIGHT: u32 = 160;
+/// A bar away from the origin, so gradient points that are offset by the bar's position twice
+/// land outside of it.
+const BAR: ((i32, i32), (i32, i32)) = ((60, 40), (100, 120));
+
+/// Fills `BAR` with `gradient` and returns the color of each of `samples`.
+fn fill_bar(gradient: Gradient, samples: &[(u32, u32)]) -> Vec<(u8, u8, u8)> {
+    fill_with(gradient, samples, |backend| {
+        backend.draw_rect(BAR.0, BAR.1, &RED.filled(), true)
+    })
+}
+
+/// Draws a white background and then the shape drawn by `draw` with `gradient` set, and returns
+/// the color of each of `samples`.
+fn fill_with<F>(gradient: Gradient, samples: &[(u32, u32)], draw: F) -> Vec<(u8, u8, u8)>
+where
+    F: FnOnce(&mut OrbtkBackend) -> Result<(), DrawingErrorKind<OrbtkError>>,
+{
+    let mut render_ctx = RenderContext2D::new(WIDTH as f64, HEIGHT as f64);
+    {
+        let mut backend = OrbtkBackend::new(&mut render_ctx, WIDTH as f64, HEIGHT as f64).unwrap();
+        backend
+            .draw_rect((0, 0), (WIDTH as i32, HEIGHT as i32), &WHITE, true)
+            .unwrap();
+        backend.gradient_fill().set(gradient);
+        draw(&mut backend).unwrap();
+    }
+    samples
+        .iter()
+        .map(|&point| orbtk_pixel(&render_ctx, WIDTH, point))
+        .collect()
+}
+
+/// The color `t` of the way from red to blue.
+fn red_to_blue(t: f64) -> (u8, u8, u8) {
+    (
+        (255.0 * (1.0 - t)).round() as u8,
+        0,
+        (255.0 * t).round() as u8,
+    )
+}
+
+#[test]
+fn vertical_gradient_spans_the_bar() {
+    let (top, bottom) = ((BAR.0).1, (BAR.1).1);
+    let height = (bottom - top) as f64;
+    let rows = [top + 1, (top + bottom) / 2, bottom - 2];
+    let samples: Vec<_> = rows.iter().map(|&y| (80, y as u32)).collect();
+
+    let colors = fill_bar(
+        Gradient::vertical(RED.to_backend_color(), BLUE.to_backend_color()),
+        &samples,
+    );
+
+    for (&y, &color) in rows.iter().zip(colors.iter()) {
+        let t = (y - top) as f64 / height;
+        assert_color_eq(color, red_to_blue(t), 16, &format!("row {}", y));
+    }
+}
+
+#[test]
+fn horizontal_gradient_spans_the_bar() {
+    let (left, right) = ((BAR.0).0, (BAR.1).0);
+    let width = (right - left) as f64;
+    let columns = [left + 1, (left + right) / 2, right - 2];
+    let samples: Vec<_> = columns.iter().map(|&x| (x as u32, 80)).collect();
+
+    let colors = fill_bar(
+        Gradient::horizontal(RED.to_backend_color(), BLUE.to_backend_color()),
+        &samples,
+    );
+
+    for (&x, &color) in columns.iter().zip(colors.iter()) {
+        let t = (x - left) as f64 / width;
+        assert_color_eq(color, red_to_blue(t), 16, &format!("column {}", x));
+    }
+}
+
+#[test]
+fn gradient_spans_the_bounds_of_a_polygon() {
+    // A triangle with the bounding box of the bar, its left edge runs along the bar's.
+    let (top, bottom) = ((BAR.0).1, (BAR.1).1);
+    let triangle = vec![((BAR.0).0, top), ((BAR.1).0, bottom), ((BAR.0).0, bottom)];
+    let height = (bottom - top) as f64;
+    let rows = [top + 10, (top + bottom) / 2, bottom - 2];
+    let samples: Vec<_> = rows.iter().map(|&y| (62, y as u32)).collect();
+
+    let colors = fill_with(
+        Gradient::vertical(RED.to_backend_color(), BLUE.to_backend_color()),
+        &samples,
+        |backend| backend.fill_polygon(triangle, &RED.filled()),
+    );
+
+    for (&y, &color) in rows.iter().zip(colors.iter()) {
+        let t = (y - top) as f64 / height;
+        assert_color_eq(color, red_to_blue(t), 16, &format!("row {}", y));
+    }
+}
+
+#[test]
+fn radial_gradient_is_centered_on_the_bar() {
+    let center = (
+        ((BAR.0).0 + (BAR.1).0) as f64 / 2.0,
+        ((BAR.0).1 + (BAR.1).1) as f64 / 2.0,
+    );
+    // Half of the bar's larger side.
+    let radius = ((BAR.1).1 - (BAR.0).1) as f64 / 2.0;
+    let samples = [(80, 80), (80, 100), (80, 60), (65, 80), (95, 80), (80, 115)];
+
+    let colors = fill_bar(
+        Gradient::radial(RED.to_backend_color(), BLUE.to_backend_color()),
+        &samples,
+    );
+
+    for (&(x, y), &color) in samples.iter().zip(colors.iter()) {
+        let (dx, dy) = (x as f64 + 0.5 - center.0, y as f64 + 0.5 - center.1);
+        let t = (dx * dx + dy * dy).sqrt() / radius;
+        assert_color_eq(color, red_to_blue(t), 16, &format!("pixel ({}, {})", x, y));
+    }
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use common::orbtk_pixel;
+use orbtk::prelude::RenderContext2D;
+use orbtk_backend::{OrbtkBackend, OrbtkError};
+use plotters::prelude::*;
+
+#[test]
+fn primitives_are_drawn_at_physical_resolution() {
+    let mut render_ctx = RenderContext2D::new(200.0, 200.0);
+    {
+        let mut backend =
+            OrbtkBackend::with_scale_factor(&mut render_ctx, 100.0, 100.0, 2.0).unwrap();
+        assert_eq!(backend.get_size(), (100, 100));
+        backend.draw_rect((10, 10), (20, 20), &RED, true).unwrap();
+        backend
+            .draw_pixel((50, 50), BLUE.to_backend_color())
+            .unwrap();
+        let green = [0, 255, 0].repeat(4);
+        backend.blit_bitmap((60, 10), (2, 2), &green).unwrap();
+    }
+
+    let pixel = |point| orbtk_pixel(&render_ctx, 200, point);
+    // The rectangle covers 20..40 physical pixels.
+    assert_eq!(pixel((21, 21)), (255, 0, 0));
+    assert_eq!(pixel((38, 38)), (255, 0, 0));
+    assert_eq!(pixel((18, 18)), (0, 0, 0));
+    assert_eq!(pixel((42, 42)), (0, 0, 0));
+    // A logical pixel is a 2x2 block.
+    for &point in [(100, 100), (101, 100), (100, 101), (101, 101)].iter() {
+        assert_eq!(pixel(point), (0, 0, 255), "draw_pixel at {:?}", point);
+    }
+    assert_eq!(pixel((99, 100)), (0, 0, 0));
+    assert_eq!(pixel((102, 101)), (0, 0, 0));
+    // The 2x2 bitmap covers 120..124 x 20..24.
+    for &point in [(120, 20), (123, 23)].iter() {
+        assert_eq!(pixel(point), (0, 255, 0), "blit_bitmap at {:?}", point);
+    }
+    assert_eq!(pixel((124, 20)), (0, 0, 0));
+    assert_eq!(pixel((120, 24)), (0, 0, 0));
+}
+
+#[test]
+#[cfg(feature = "bundled-font")]
+fn text_size_is_reported_in_logical_pixels() {
+    let mut physical = RenderContext2D::new(200.0, 200.0);
+    let hidpi = OrbtkBackend::with_scale_factor(&mut physical, 100.0, 100.0, 2.0).unwrap();
+    let mut logical = RenderContext2D::new(100.0, 100.0);
+    let lodpi = OrbtkBackend::new(&mut logical, 100.0, 100.0).unwrap();
+
+    let style = ("sans-serif", 14).into_font();
+    let (width, height) = hidpi.estimate_text_size("0.125", &style).unwrap();
+    let (lodpi_width, lodpi_height) = lodpi.estimate_text_size("0.125", &style).unwrap();
+    assert!((width as i32 - lodpi_width as i32).abs() <= 1);
+    assert_eq!(height, lodpi_height);
+    let metrics = hidpi.font_metrics(FontFamily::SansSerif, FontStyle::Normal, 14.0);
+    assert_eq!(height, metrics.height().ceil() as u32);
+}
+
+#[test]
+fn non_positive_scale_is_reported_as_such() {
+    let mut render_ctx = RenderContext2D::new(800.0, 600.0);
+    assert!(matches!(
+        OrbtkBackend::with_scale_factor(&mut render_ctx, 800.0, 600.0, 0.0),
+        Err(OrbtkError::InvalidScaleFactor { scale }) if scale == 0.0
+    ));
+}
@@ -0,0 +1,28 @@
+#![cfg(not(feature = "bundled-font"))]
+
+mod common;
+
+use common::roboto;
+use orbtk_backend::{OffscreenCanvas, OrbtkError};
+use plotters::prelude::*;
+
+#[test]
+fn text_needs_a_registered_font() {
+    let mut canvas = OffscreenCanvas::new(100, 40).unwrap();
+    let mut backend = canvas.backend().unwrap();
+    let style = ("sans-serif", 12).into_font().color(&BLACK);
+
+    match backend.estimate_text_size("1.0", &style) {
+        Err(DrawingErrorKind::DrawingError(OrbtkError::NoFont)) => {}
+        other => panic!("expected a missing font error, got {:?}", other),
+    }
+    assert!(backend.draw_text("1.0", &style, (0, 0)).is_err());
+
+    // The first registered font stands in for every family.
+    backend
+        .register_font(FontFamily::Monospace, FontStyle::Normal, "Roboto", roboto())
+        .unwrap();
+    let (width, height) = backend.estimate_text_size("1.0", &style).unwrap();
+    assert!(width > 0 && height > 0);
+    backend.draw_text("1.0", &style, (0, 0)).unwrap();
+}
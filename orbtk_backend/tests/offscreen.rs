@@ -0,0 +1,44 @@
+use orbtk_backend::{OffscreenCanvas, OrbtkError};
+use plotters::prelude::*;
+
+#[test]
+fn zero_sized_canvas_is_rejected() {
+    assert!(matches!(
+        OffscreenCanvas::new(0, 10),
+        Err(OrbtkError::InvalidTarget { .. })
+    ));
+}
+
+#[test]
+fn renders_to_rgba() {
+    let mut canvas = OffscreenCanvas::new(20, 10).unwrap();
+    {
+        let root = canvas.backend().unwrap().into_drawing_area();
+        root.fill(&WHITE).unwrap();
+        root.draw(&Rectangle::new([(0, 0), (10, 10)], RED.filled()))
+            .unwrap();
+    }
+
+    let rgba = canvas.to_rgba();
+    assert_eq!(rgba.len(), 20 * 10 * 4);
+    let pixel = |x: usize, y: usize| &rgba[(y * 20 + x) * 4..(y * 20 + x) * 4 + 4];
+    assert_eq!(pixel(5, 5), &[255, 0, 0, 255]);
+    assert_eq!(pixel(15, 5), &[255, 255, 255, 255]);
+}
+
+#[test]
+fn saves_png() {
+    let mut canvas = OffscreenCanvas::new(8, 8).unwrap();
+    canvas
+        .backend()
+        .unwrap()
+        .into_drawing_area()
+        .fill(&BLUE)
+        .unwrap();
+
+    let path = std::env::temp_dir().join("orbtk_backend_saves_png.png");
+    canvas.save_png(&path).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    assert_eq!(&bytes[1..4], b"PNG");
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use common::{assert_color_eq, bitmap_pixel, orbtk_pixel};
+use orbtk::prelude::RenderContext2D;
+use orbtk_backend::OrbtkBackend;
+use plotters::prelude::*;
+
+const WIDTH: u32 = 60;
+const HEIGHT: u32 = 40;
+
+/// Draws overlapping semi-transparent pixels with a vector rectangle between two batches.
+fn draw_pixels<DB: DrawingBackend>(mut backend: DB) {
+    backend
+        .draw_rect((0, 0), (WIDTH as i32, HEIGHT as i32), &WHITE, true)
+        .unwrap();
+    for y in 5..25 {
+        for x in 5..35 {
+            backend
+                .draw_pixel((x, y), RED.mix(0.5).to_backend_color())
+                .unwrap();
+        }
+    }
+    // Overlaps the red pixels, and each of these pixels is drawn twice.
+    for _ in 0..2 {
+        for y in 15..35 {
+            for x in 20..50 {
+                backend
+                    .draw_pixel((x, y), BLUE.mix(0.4).to_backend_color())
+                    .unwrap();
+            }
+        }
+    }
+    // Has to cover the pixels drawn so far, and be covered by the ones drawn after it.
+    backend.draw_rect((10, 10), (40, 30), &GREEN, true).unwrap();
+    for y in 20..38 {
+        for x in 30..58 {
+            backend
+                .draw_pixel((x, y), BLACK.mix(0.3).to_backend_color())
+                .unwrap();
+        }
+    }
+    backend.present().unwrap();
+}
+
+#[test]
+fn batched_pixels_match_bitmap_backend() {
+    let mut render_ctx = RenderContext2D::new(WIDTH as f64, HEIGHT as f64);
+    draw_pixels(OrbtkBackend::new(&mut render_ctx, WIDTH as f64, HEIGHT as f64).unwrap());
+
+    let mut buffer = vec![0u8; (WIDTH * HEIGHT * 3) as usize];
+    draw_pixels(BitMapBackend::with_buffer(&mut buffer, (WIDTH, HEIGHT)));
+
+    for y in 0..HEIGHT {
+        for x in 0..WIDTH {
+            // BitMapBackend fills the bottom right edge of rectangles as well, OrbTk doesn't.
+            if x == 40 || y == 30 {
+                continue;
+            }
+            assert_color_eq(
+                orbtk_pixel(&render_ctx, WIDTH, (x, y)),
+                bitmap_pixel(&buffer, WIDTH, (x, y)),
+                2,
+                &format!("pixel {:?}", (x, y)),
+            );
+        }
+    }
+}
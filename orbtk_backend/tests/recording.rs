@@ -0,0 +1,153 @@
+use orbtk_backend::{
+    ClipStack, DisplayList, DrawCommand, Gradient, GradientFill, OffscreenCanvas, RecordingBackend,
+};
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use plotters_backend::DrawingErrorKind;
+
+#[cfg(feature = "bundled-font")]
+fn draw_chart<DB: DrawingBackend>(backend: DB) {
+    let root = backend.into_drawing_area();
+    root.fill(&WHITE).unwrap();
+    let mut chart = ChartBuilder::on(&root)
+        .caption("Recorded", ("sans-serif", 20))
+        .x_label_area_size(20)
+        .y_label_area_size(30)
+        .build_cartesian_2d(0f32..10f32, 0f32..10f32)
+        .unwrap();
+    chart.configure_mesh().draw().unwrap();
+    chart
+        .draw_series(LineSeries::new(
+            vec![(0.0, 0.0), (5.0, 8.0), (10.0, 3.0)],
+            &RED,
+        ))
+        .unwrap();
+}
+
+#[test]
+fn records_primitives_in_order() {
+    let mut list = DisplayList::new(200, 100);
+    {
+        let mut backend = RecordingBackend::new(&mut list).unwrap();
+        backend.draw_line((0, 0), (10, 10), &BLACK).unwrap();
+        backend.draw_pixel((3, 4), RED.to_backend_color()).unwrap();
+    }
+
+    assert_eq!(list.size(), (200, 100));
+    assert_eq!(list.commands().len(), 2);
+    assert!(matches!(
+        list.commands()[0],
+        DrawCommand::Line {
+            from: (0, 0),
+            to: (10, 10),
+            ..
+        }
+    ));
+    assert!(matches!(
+        list.commands()[1],
+        DrawCommand::Pixel { point: (3, 4), .. }
+    ));
+}
+
+#[test]
+#[cfg(feature = "bundled-font")]
+fn replay_matches_direct_drawing() {
+    let (width, height) = (300, 200);
+
+    let mut direct = OffscreenCanvas::new(width, height).unwrap();
+    draw_chart(direct.backend().unwrap());
+
+    let mut list = DisplayList::new(width, height);
+    draw_chart(RecordingBackend::new(&mut list).unwrap());
+    let mut replayed = OffscreenCanvas::new(width, height).unwrap();
+    list.replay(&mut replayed.backend().unwrap()).unwrap();
+
+    assert!(direct.to_rgba() == replayed.to_rgba());
+}
+
+#[test]
+fn replaying_text_onto_bitmap_backend_fails() {
+    let mut list = DisplayList::new(100, 50);
+    {
+        let mut backend = RecordingBackend::new(&mut list).unwrap();
+        let style = ("sans-serif", 12).into_font().color(&BLACK);
+        backend.draw_text("Recorded", &style, (10, 10)).unwrap();
+    }
+
+    let mut buffer = vec![0u8; 100 * 50 * 3];
+    let mut bitmap = BitMapBackend::with_buffer(&mut buffer, (100, 50));
+    assert!(matches!(
+        list.replay(&mut bitmap),
+        Err(DrawingErrorKind::FontError(_))
+    ));
+}
+
+/// A rectangle filled with a gradient and a circle, both clipped, and an unclipped diagonal.
+fn draw_clipped<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    clip: &ClipStack,
+    gradient: &GradientFill,
+) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+    root.fill(&WHITE)?;
+    {
+        let _clip = clip.push((20, 20), (80, 80));
+        gradient.set(Gradient::vertical(
+            RED.to_backend_color(),
+            BLUE.to_backend_color(),
+        ));
+        root.draw(&Rectangle::new([(0, 0), (100, 60)], RED.filled()))?;
+        gradient.clear();
+        root.draw(&Circle::new((50, 70), 30, GREEN.filled()))?;
+    }
+    root.draw(&PathElement::new(vec![(0, 99), (99, 0)], &BLACK))
+}
+
+#[test]
+fn clip_and_gradients_are_recorded() {
+    let mut direct = OffscreenCanvas::new(100, 100).unwrap();
+    {
+        let backend = direct.backend().unwrap();
+        let (clip, gradient) = (backend.clip_stack(), backend.gradient_fill());
+        draw_clipped(&backend.into_drawing_area(), &clip, &gradient).unwrap();
+    }
+
+    let mut list = DisplayList::new(100, 100);
+    {
+        let backend = RecordingBackend::new(&mut list).unwrap();
+        let (clip, gradient) = (backend.clip_stack(), backend.gradient_fill());
+        draw_clipped(&backend.into_drawing_area(), &clip, &gradient).unwrap();
+    }
+    let state: Vec<_> = list
+        .commands()
+        .iter()
+        .filter_map(|command| match command {
+            DrawCommand::Clip { rect } => Some(format!("clip {:?}", rect)),
+            DrawCommand::Fill { gradient } => Some(format!("fill {}", gradient.is_some())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        state,
+        [
+            "clip Some(((20, 20), (80, 80)))",
+            "fill true",
+            "fill false",
+            "clip None",
+        ]
+    );
+
+    let mut replayed = OffscreenCanvas::new(100, 100).unwrap();
+    list.replay_onto_backend(&mut replayed.backend().unwrap())
+        .unwrap();
+    assert!(direct.to_rgba() == replayed.to_rgba());
+
+    // Plain plotters replay skips the clip and gradients.
+    let mut unclipped = OffscreenCanvas::new(100, 100).unwrap();
+    list.replay(&mut unclipped.backend().unwrap()).unwrap();
+    let pixel = |canvas: &OffscreenCanvas| {
+        let index = (10 * 100 + 10) * 4;
+        canvas.to_rgba()[index..index + 3].to_vec()
+    };
+    assert_eq!(pixel(&replayed), [255, 255, 255]);
+    assert_eq!(pixel(&unclipped), [255, 0, 0]);
+}
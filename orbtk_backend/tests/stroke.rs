@@ -0,0 +1,104 @@
+use orbtk_backend::{LineCap, LineJoin, OffscreenCanvas, OrbtkBackend};
+use plotters::prelude::*;
+
+/// Draws black strokes onto a transparent 100x100 canvas and returns the alpha of every pixel.
+fn render(draw: impl FnOnce(&mut OrbtkBackend)) -> Vec<u8> {
+    let mut canvas = OffscreenCanvas::new(100, 100).unwrap();
+    draw(&mut canvas.backend().unwrap());
+    canvas.to_rgba().chunks(4).map(|pixel| pixel[3]).collect()
+}
+
+fn assert_inked(alpha: &[u8], points: &[(usize, usize)], inked: bool, what: &str) {
+    for &(x, y) in points.iter() {
+        let a = alpha[y * 100 + x];
+        assert!(
+            if inked { a > 200 } else { a < 50 },
+            "{}: pixel ({}, {}) has alpha {}",
+            what,
+            x,
+            y,
+            a
+        );
+    }
+}
+
+/// A horizontal line from (20, 30) to (60, 30), 10 pixels wide.
+fn line_with_cap(cap: LineCap) -> Vec<u8> {
+    render(|backend| {
+        backend.set_line_cap(cap);
+        // Butt caps and miter joins are drawn natively unless something else differs.
+        backend.set_line_join(LineJoin::Round);
+        backend
+            .draw_line((20, 30), (60, 30), &BLACK.stroke_width(10))
+            .unwrap();
+    })
+}
+
+#[test]
+fn caps_extend_the_line_ends() {
+    let butt = line_with_cap(LineCap::Butt);
+    assert_inked(&butt, &[(22, 30), (58, 26)], true, "butt");
+    assert_inked(&butt, &[(17, 30), (63, 30)], false, "butt");
+
+    let round = line_with_cap(LineCap::Round);
+    assert_inked(&round, &[(16, 30), (63, 30), (20, 26)], true, "round");
+    // Outside the half circle, but inside the square cap.
+    assert_inked(&round, &[(15, 26), (64, 33)], false, "round");
+
+    let square = line_with_cap(LineCap::Square);
+    assert_inked(&square, &[(16, 30), (15, 26), (64, 33)], true, "square");
+    assert_inked(&square, &[(13, 30), (66, 30)], false, "square");
+}
+
+/// A right angle turning at (50, 50), 10 pixels wide. The miter tip lies about 7.07 pixels below
+/// the vertex, the bevel cuts the corner about 3.54 pixels below it.
+fn corner(join: LineJoin, miter_limit: f64) -> Vec<u8> {
+    render(|backend| {
+        backend.set_line_join(join);
+        backend.set_miter_limit(miter_limit);
+        backend
+            .draw_path(vec![(10, 10), (50, 50), (90, 10)], &BLACK.stroke_width(10))
+            .unwrap();
+    })
+}
+
+#[test]
+fn miter_joins_fall_back_to_bevel_above_the_limit() {
+    // The miter of a right angle is sqrt(2) times the line width.
+    let miter = corner(LineJoin::Miter, 2.0);
+    assert_inked(&miter, &[(50, 55)], true, "miter");
+    assert_inked(&miter, &[(50, 58)], false, "miter");
+
+    let beveled = corner(LineJoin::Miter, 1.2);
+    assert_inked(&beveled, &[(50, 52)], true, "beveled miter");
+    assert_inked(&beveled, &[(50, 55)], false, "beveled miter");
+    assert_eq!(beveled, corner(LineJoin::Bevel, 2.0));
+
+    let round = corner(LineJoin::Round, 2.0);
+    assert_inked(&round, &[(50, 53)], true, "round");
+    assert_inked(&round, &[(50, 56)], false, "round");
+}
+
+#[test]
+fn closed_paths_are_joined_at_the_start() {
+    let square = |path: Vec<(i32, i32)>| {
+        render(move |backend| {
+            backend.set_miter_limit(2.0);
+            backend.draw_path(path, &BLACK.stroke_width(10)).unwrap();
+        })
+    };
+
+    let closed = square(vec![(20, 20), (60, 20), (60, 60), (20, 60), (20, 20)]);
+    // Every corner, including the one the path starts and ends at, has its miter.
+    assert_inked(
+        &closed,
+        &[(16, 16), (63, 16), (63, 63), (16, 63)],
+        true,
+        "closed",
+    );
+    assert_inked(&closed, &[(40, 40), (40, 12)], false, "closed");
+
+    // An open path ends with butt caps instead.
+    let open = square(vec![(20, 20), (60, 20), (60, 60), (20, 60), (20, 24)]);
+    assert_inked(&open, &[(16, 16)], false, "open");
+}
@@ -0,0 +1,36 @@
+use orbtk_backend::{OffscreenCanvas, OrbtkError, TeeBackend, TeeError};
+use plotters::prelude::*;
+
+#[test]
+fn draws_to_both_backends() {
+    let mut canvas = OffscreenCanvas::new(40, 20).unwrap();
+    let mut buffer = vec![0u8; 30 * 20 * 3];
+    {
+        let tee = TeeBackend::new(
+            canvas.backend().unwrap(),
+            BitMapBackend::with_buffer(&mut buffer, (30, 20)),
+        );
+        assert_eq!(tee.get_size(), (30, 20));
+
+        let root = tee.into_drawing_area();
+        root.fill(&BLUE).unwrap();
+    }
+
+    let rgba = canvas.to_rgba();
+    assert_eq!(&rgba[0..4], &[0, 0, 255, 255]);
+    assert_eq!(&buffer[0..3], &[0, 0, 255]);
+    // Only the shared area is drawn.
+    assert_eq!(rgba[(10 * 40 + 35) * 4 + 3], 0);
+}
+
+#[test]
+fn errors_name_the_failing_backend() {
+    let error: TeeError<OrbtkError, OrbtkError> = TeeError::Second(OrbtkError::InvalidTarget {
+        width: 0.0,
+        height: 0.0,
+    });
+    assert_eq!(
+        error.to_string(),
+        "second backend: invalid drawing target size 0x0"
+    );
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use common::{backend_with_roboto, roboto};
+use orbtk_backend::{clear_text_cache, set_text_cache_capacity, text_cache_stats, OffscreenCanvas};
+use plotters::prelude::*;
+
+// The cache is shared by the whole process, so everything is checked in a single test.
+#[test]
+fn repeated_measurements_hit_the_cache() {
+    clear_text_cache();
+    let mut canvas = OffscreenCanvas::new(100, 100).unwrap();
+    let mut backend = backend_with_roboto(&mut canvas);
+    let style = ("sans-serif", 12).into_font();
+
+    let first = backend.estimate_text_size("0.125", &style).unwrap();
+    let second = backend.estimate_text_size("0.125", &style).unwrap();
+    assert_eq!(first, second);
+    let stats = text_cache_stats();
+    assert_eq!((stats.hits, stats.misses, stats.len), (1, 1, 1));
+
+    // A different size is a different entry.
+    backend
+        .estimate_text_size("0.125", &("sans-serif", 13).into_font())
+        .unwrap();
+    assert_eq!(text_cache_stats().misses, 2);
+
+    set_text_cache_capacity(1);
+    assert_eq!(text_cache_stats().len, 1);
+    backend.estimate_text_size("0.25", &style).unwrap();
+    backend.estimate_text_size("0.125", &style).unwrap();
+    let stats = text_cache_stats();
+    assert_eq!((stats.misses, stats.len), (4, 1));
+
+    // Using an entry keeps it, the least recently used one is dropped instead.
+    set_text_cache_capacity(2);
+    backend.estimate_text_size("0.25", &style).unwrap();
+    backend.estimate_text_size("0.125", &style).unwrap();
+    backend.estimate_text_size("0.5", &style).unwrap();
+    backend.estimate_text_size("0.125", &style).unwrap();
+    let stats = text_cache_stats();
+    assert_eq!((stats.hits, stats.misses, stats.len), (3, 6, 2));
+    backend.estimate_text_size("0.25", &style).unwrap();
+    assert_eq!(text_cache_stats().misses, 7);
+
+    // Other data under the same name isn't measured with the widths of the first font.
+    set_text_cache_capacity(1024);
+    let copy: &'static [u8] = Box::leak(roboto().to_vec().into_boxed_slice());
+    let mut other_canvas = OffscreenCanvas::new(100, 100).unwrap();
+    let mut other = other_canvas.backend().unwrap();
+    other
+        .register_font(FontFamily::SansSerif, FontStyle::Normal, "Roboto", copy)
+        .unwrap();
+    let misses = text_cache_stats().misses;
+    other.estimate_text_size("0.125", &style).unwrap();
+    assert_eq!(text_cache_stats().misses, misses + 1);
+    backend.estimate_text_size("0.125", &style).unwrap();
+    assert_eq!(text_cache_stats().misses, misses + 1);
+
+    clear_text_cache();
+    assert_eq!(text_cache_stats(), Default::default());
+}
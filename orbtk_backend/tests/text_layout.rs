@@ -0,0 +1,138 @@
+mod common;
+
+use common::backend_with_roboto;
+use orbtk_backend::OffscreenCanvas;
+use plotters::prelude::*;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+
+const SIZES: [f64; 5] = [8.0, 12.0, 17.5, 24.0, 40.0];
+/// Antialiasing may touch one pixel beyond the text box.
+const SLACK: u32 = 1;
+const TEXTS: [&str; 5] = ["0", "1.25", "-3e-5", "iiii", "Wide MMM"];
+
+/// Returns the leftmost and topmost, and the rightmost and bottommost inked pixel of a canvas.
+fn ink_bounds(canvas: &OffscreenCanvas) -> Option<((u32, u32), (u32, u32))> {
+    let (width, _) = canvas.size();
+    let rgba = canvas.to_rgba();
+    let inked: Vec<(u32, u32)> = (0..rgba.len() / 4)
+        .filter(|&i| rgba[i * 4 + 3] > 0)
+        .map(|i| (i as u32 % width, i as u32 / width))
+        .collect();
+    let xs = inked.iter().map(|&(x, _)| x);
+    let ys = inked.iter().map(|&(_, y)| y);
+    Some((
+        (xs.clone().min()?, ys.clone().min()?),
+        (xs.max()?, ys.max()?),
+    ))
+}
+
+/// Draws `text` anchored at `anchor` and returns the estimated size and the ink bounds.
+fn draw(
+    text: &str,
+    size: f64,
+    pos: Pos,
+    anchor: (i32, i32),
+) -> ((u32, u32), ((u32, u32), (u32, u32))) {
+    let mut canvas = OffscreenCanvas::new(400, 80).unwrap();
+    let mut backend = backend_with_roboto(&mut canvas);
+    let style = ("sans-serif", size).into_font().color(&BLACK).pos(pos);
+    let estimate = backend.estimate_text_size(text, &style).unwrap();
+    backend.draw_text(text, &style, anchor).unwrap();
+    drop(backend);
+    (estimate, ink_bounds(&canvas).expect("nothing was drawn"))
+}
+
+/// The side bearings of the first and last glyph are part of the estimated width but aren't
+/// inked, together they stay below a fifth of the font size in Roboto.
+fn max_bearings(size: f64) -> u32 {
+    (size * 0.2).ceil() as u32 + SLACK
+}
+
+#[test]
+fn drawn_text_fits_the_estimated_size() {
+    for &size in SIZES.iter() {
+        for &text in TEXTS.iter() {
+            let what = format!("{:?} at {}px", text, size);
+            let draw = |h_pos, x| draw(text, size, Pos::new(h_pos, VPos::Top), (x, 10));
+
+            let ((width, _), ((left, _), (right, _))) = draw(HPos::Left, 20);
+            assert!(
+                left + SLACK >= 20 && right <= 20 + width + SLACK,
+                "left anchored {}",
+                what
+            );
+            // An estimate that is too wide fits the ink as well, so the ink has to fill it.
+            let inked = right - left + 1;
+            assert!(
+                inked + max_bearings(size) >= width,
+                "{} inks {} of {} pixels",
+                what,
+                inked,
+                width
+            );
+
+            let ((width, _), ((left, _), (right, _))) = draw(HPos::Right, 380);
+            assert!(
+                left + width + SLACK >= 380 && right <= 380 + SLACK,
+                "right anchored {}",
+                what
+            );
+
+            let ((width, _), ((left, _), (right, _))) = draw(HPos::Center, 200);
+            let half = (width + 1) / 2;
+            assert!(
+                left + half + SLACK >= 200 && right <= 200 + half + SLACK,
+                "centered {}",
+                what
+            );
+        }
+    }
+}
+
+#[test]
+fn drawn_text_fills_the_estimated_height() {
+    for &size in SIZES.iter() {
+        // Capitals reach almost to the ascent and `g` almost to the descent.
+        let ((_, height), ((_, top), (_, bottom))) =
+            draw("Mg", size, Pos::new(HPos::Left, VPos::Top), (20, 10));
+        let what = format!("\"Mg\" at {}px", size);
+        assert!(
+            top + SLACK >= 10 && bottom <= 10 + height + SLACK,
+            "{} outside of its box",
+            what
+        );
+        assert!(
+            (bottom - top + 1) as f64 >= 0.7 * height as f64,
+            "{} inks {} of {} rows",
+            what,
+            bottom - top + 1,
+            height
+        );
+    }
+}
+
+#[test]
+fn lines_are_stacked_and_anchored_as_a_block() {
+    let mut canvas = OffscreenCanvas::new(200, 200).unwrap();
+    let mut backend = backend_with_roboto(&mut canvas);
+    let style = ("sans-serif", 20)
+        .into_font()
+        .color(&BLACK)
+        .pos(Pos::new(HPos::Left, VPos::Bottom));
+
+    let metrics = backend.font_metrics(FontFamily::SansSerif, FontStyle::Normal, 20.0);
+    let (short, _) = backend.estimate_text_size("1", &style).unwrap();
+    let (long, _) = backend.estimate_text_size("1000", &style).unwrap();
+    let (width, height) = backend.estimate_text_size("1\n1000\n1", &style).unwrap();
+    assert!(short < long);
+    assert_eq!(width, long);
+    assert_eq!(height, metrics.block_height(3).ceil() as u32);
+
+    backend.draw_text("1\n1000\n1", &style, (10, 150)).unwrap();
+    drop(backend);
+
+    let ((_, top), (_, bottom)) = ink_bounds(&canvas).unwrap();
+    // Three lines of digits, the bottom of the block sits on the anchor.
+    assert!(top + SLACK >= 150 - height && bottom <= 150 + SLACK);
+    assert!(bottom - top > 2 * metrics.height() as u32);
+}
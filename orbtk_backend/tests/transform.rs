@@ -0,0 +1,152 @@
+mod common;
+
+use common::orbtk_pixel;
+use orbtk::prelude::RenderContext2D;
+#[cfg(feature = "bundled-font")]
+use orbtk_backend::OffscreenCanvas;
+use orbtk_backend::{OrbtkBackend, OrbtkError};
+use plotters::prelude::*;
+#[cfg(feature = "bundled-font")]
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+
+#[test]
+#[cfg(feature = "bundled-font")]
+fn rotated_text_keeps_the_host_transform() {
+    let mut render_ctx = RenderContext2D::new(100.0, 100.0);
+    render_ctx.set_transform(1.0, 0.0, 0.0, 1.0, 30.0, 20.0);
+    {
+        let host = [1.0, 0.0, 0.0, 1.0, 30.0, 20.0];
+        let mut backend =
+            OrbtkBackend::with_transform(&mut render_ctx, 70.0, 80.0, 1.0, host).unwrap();
+        let style = ("sans-serif", 12)
+            .into_font()
+            .transform(FontTransform::Rotate90)
+            .color(&BLACK);
+        backend.draw_text("label", &style, (60, 5)).unwrap();
+        backend.draw_rect((0, 0), (10, 10), &RED, true).unwrap();
+    }
+
+    let alpha = |x: u32, y: u32| render_ctx.data()[(y * 100 + x) as usize] >> 24;
+    let inked = |xs: std::ops::Range<u32>, ys: std::ops::Range<u32>| {
+        ys.flat_map(|y| xs.clone().map(move |x| (x, y)))
+            .filter(|&(x, y)| alpha(x, y) > 0)
+            .count()
+    };
+
+    // The label hangs to the left of its anchor at (60, 5), moved by the host's translation.
+    assert!(inked(78..90, 25..55) > 0);
+    // Without the host transform it would have ended up at the untranslated position.
+    assert_eq!(inked(45..60, 0..25), 0);
+    // The rect after the rotated label still lands in the host's translated frame.
+    assert_eq!(orbtk_pixel(&render_ctx, 100, (35, 25)), (255, 0, 0));
+    assert_eq!(alpha(5, 5), 0);
+}
+
+/// Where an unrotated text box of `w` x `h` lies relative to its anchor, as `(x0, y0, x1, y1)`.
+#[cfg(feature = "bundled-font")]
+fn anchored_box(pos: Pos, (w, h): (f64, f64)) -> (f64, f64, f64, f64) {
+    let (x0, x1) = match pos.h_pos {
+        HPos::Left => (0.0, w),
+        HPos::Center => (-w / 2.0, w / 2.0),
+        HPos::Right => (-w, 0.0),
+    };
+    let (y0, y1) = match pos.v_pos {
+        VPos::Top => (0.0, h),
+        VPos::Center => (-h / 2.0, h / 2.0),
+        VPos::Bottom => (-h, 0.0),
+    };
+    (x0, y0, x1, y1)
+}
+
+#[test]
+#[cfg(feature = "bundled-font")]
+fn rotated_labels_hang_on_the_anchored_side() {
+    const ANCHOR: (i32, i32) = (100, 100);
+    /// Antialiasing may touch a pixel beyond the box.
+    const SLACK: f64 = 1.0;
+
+    for transform in [
+        FontTransform::Rotate90,
+        FontTransform::Rotate180,
+        FontTransform::Rotate270,
+    ]
+    .iter()
+    {
+        for &h_pos in [HPos::Left, HPos::Center, HPos::Right].iter() {
+            for &v_pos in [VPos::Top, VPos::Center, VPos::Bottom].iter() {
+                let pos = Pos::new(h_pos, v_pos);
+                let style = ("sans-serif", 20)
+                    .into_font()
+                    .transform(transform.clone())
+                    .color(&BLACK)
+                    .pos(pos);
+                let mut canvas = OffscreenCanvas::new(200, 200).unwrap();
+                // The estimate is the size of the unrotated text.
+                let (w, h) = {
+                    let mut backend = canvas.backend().unwrap();
+                    backend.draw_text("Placement", &style, ANCHOR).unwrap();
+                    backend.estimate_text_size("Placement", &style).unwrap()
+                };
+
+                // Rotate the box clockwise around the anchor: by 90 degrees the text runs down
+                // and its top faces right, by 180 upside down and by 270 up with its top left.
+                let (x0, y0, x1, y1) = anchored_box(pos, (w as f64, h as f64));
+                let (left, top, right, bottom) = match transform {
+                    FontTransform::Rotate90 => (-y1, x0, -y0, x1),
+                    FontTransform::Rotate180 => (-x1, -y1, -x0, -y0),
+                    _ => (y0, -x1, y1, -x0),
+                };
+
+                let rgba = canvas.to_rgba();
+                let inked: Vec<(f64, f64)> = (0..200 * 200)
+                    .filter(|&i| rgba[i * 4 + 3] > 0)
+                    .map(|i| ((i % 200) as f64 - 100.0, (i / 200) as f64 - 100.0))
+                    .collect();
+                let what = format!("{:?} {:?} {:?}", transform, h_pos, v_pos);
+                assert!(!inked.is_empty(), "{} wasn't drawn", what);
+                for &(x, y) in &inked {
+                    assert!(
+                        x + 1.0 + SLACK >= left
+                            && x <= right + SLACK
+                            && y + 1.0 + SLACK >= top
+                            && y <= bottom + SLACK,
+                        "{} inks ({}, {}) relative to the anchor, outside of {:?}",
+                        what,
+                        x,
+                        y,
+                        (left, top, right, bottom)
+                    );
+                }
+
+                // The text runs along the whole rotated width, not just a corner of the box.
+                let along: Vec<f64> = match transform {
+                    FontTransform::Rotate180 => inked.iter().map(|p| p.0).collect(),
+                    _ => inked.iter().map(|p| p.1).collect(),
+                };
+                let along = along.iter().cloned().fold(f64::MIN, f64::max)
+                    - along.iter().cloned().fold(f64::MAX, f64::min)
+                    + 1.0;
+                assert!(
+                    along >= 0.8 * w as f64,
+                    "{} inks {} of {} pixels",
+                    what,
+                    along,
+                    w
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn non_finite_transform_is_rejected() {
+    let mut render_ctx = RenderContext2D::new(10.0, 10.0);
+    let transform = [1.0, 0.0, 0.0, 1.0, f64::NAN, 0.0];
+    assert!(matches!(
+        OrbtkBackend::with_transform(&mut render_ctx, 10.0, 10.0, 1.0, transform),
+        Err(OrbtkError::NonFiniteCoordinate {
+            context: "transform",
+            ..
+        })
+    ));
+}